@@ -1,11 +1,27 @@
+use std::fmt;
+
+use serde::de::value::{MapAccessDeserializer, StrDeserializer};
+use serde::de::{self, MapAccess, Visitor};
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::Serializer;
 
 /// Top-level list of supported operations in the SQL model.
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub enum Operation {
     Insert(Insert),
     Upsert(Upsert),
+    Statement(Statement),
+    Call(Call),
+    Truncate(Truncate),
+    Delete(Delete),
+    /// Starts a snapshot: the following operations replace the table contents.
+    SnapshotBegin(Snapshot),
+    /// Ends a snapshot, the table contents are replaced atomically.
+    SnapshotEnd(Snapshot),
+    /// Operations executed atomically, all or none of them are applied.
+    Transaction(Vec<Operation>),
 }
 
 /// SQL Insert operation
@@ -23,25 +39,148 @@ pub struct Upsert {
     pub uniq_idx: String,
 }
 
+/// Raw SQL statement with positional parameters.
+///
+/// The statement is executed only if the sink allows it.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Statement {
+    pub sql: String,
+    pub params: Vec<Value>,
+}
+
+/// Stored procedure or function call with positional arguments.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Call {
+    pub routine: String,
+    pub args: Vec<Value>,
+    #[serde(default)]
+    pub kind: RoutineKind,
+}
+
+/// Kind of the called routine.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Default)]
+pub enum RoutineKind {
+    /// Called via `SELECT routine(...)`
+    #[default]
+    Function,
+    /// Called via `CALL routine(...)`
+    Procedure,
+}
+
+/// Removal of all rows from the table
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Truncate {
+    pub table: String,
+}
+
+/// Removal of the rows matching all values
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Delete {
+    pub table: String,
+    pub values: Vec<Value>,
+}
+
+/// Snapshot boundary marker
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Snapshot {
+    pub table: String,
+}
+
 /// Value with SQL column name and supported SQL type.
+///
+/// The value is either the text representation in `raw_value`, or the typed JSON value in
+/// `typed_value` that is bound without re-parsing when it matches the type.
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct Value {
     pub column: String,
+    #[serde(default)]
     pub raw_value: String,
+    /// Typed JSON value, takes precedence over `raw_value`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typed_value: Option<serde_json::Value>,
     #[serde(rename = "type")]
     pub type_: Type,
+    /// SQL expression applied to the value placeholder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<Cast>,
+    /// Encoding of `Bytes` values, raw UTF-8 if absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// Value generated by the sink when the operation is written, replaces the given value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated: Option<Generated>,
 }
 
-/// Supported SQL data types.
+impl Value {
+    /// Value given by its text representation, without a cast, encoding or typed value.
+    pub fn new(column: impl Into<String>, raw_value: impl Into<String>, type_: Type) -> Self {
+        Self {
+            column: column.into(),
+            raw_value: raw_value.into(),
+            typed_value: None,
+            type_,
+            cast: None,
+            encoding: None,
+            generated: None,
+        }
+    }
+}
+
+/// SQL expression applied to the value placeholder.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub enum Cast {
+    /// Cast to the type, e.g. `$1::geometry`
+    Type(String),
+    /// Call of the single-argument function, e.g. `ST_GeomFromGeoJSON($1)`
+    Function(String),
+}
+
+/// Kind of the value generated by the sink.
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+pub enum Generated {
+    /// Current time, in the representation of the value type
+    Now,
+    /// Random UUID
+    UuidV4,
+    /// Time-ordered UUID
+    UuidV7,
+}
+
+/// Text encoding of binary values.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Default)]
+pub enum Encoding {
+    /// UTF-8 bytes of the value as is
+    #[default]
+    Raw,
+    /// Standard base64 alphabet, padding is optional
+    Base64,
+    /// URL-safe base64 alphabet, padding is optional
+    Base64Url,
+    /// Hexadecimal digits of any case
+    Hex,
+}
+
+/// Supported SQL data types.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(remote = "Self")]
 pub enum Type {
     Bool,
-    Char,
+    /// Fixed-length character string, values shorter than the length are padded with spaces.
+    Char(Option<u32>),
 
+    /// Single-byte integer, e.g. Postgres `"char"`.
+    TinyInt,
     SmallInt,
     Int,
     BigInt,
 
+    /// Unsigned 64-bit integer, stored as `NUMERIC` on Postgres.
+    UnsignedBigInt,
+    /// Signed 128-bit integer, stored as `NUMERIC` on Postgres.
+    Int128,
+    /// Unsigned 128-bit integer, stored as `NUMERIC` on Postgres.
+    UnsignedInt128,
+
     Float,
     DoublePrecision,
 
@@ -51,10 +190,122 @@ pub enum Type {
     Numeric,
 
     Timestamp,
+    TimestampTz,
     Date,
     Time,
+    Interval,
 
     Uuid,
 
+    Inet,
+    Cidr,
+    MacAddr,
+
     Json,
+
+    Array(ElementType),
+
+    /// Enum or domain type with the given name, the value is its text representation.
+    Enum(String),
+}
+
+impl Serialize for Type {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Type::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Type {
+    /// Accepts records serialized before `Char` became a character type,
+    /// their `"Char"` values were single-byte integers.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TypeVisitor;
+
+        impl<'de> Visitor<'de> for TypeVisitor {
+            type Value = Type;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("type name or map with a single type name key")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match v {
+                    "Char" => Ok(Type::TinyInt),
+                    _ => Type::deserialize(StrDeserializer::new(v)),
+                }
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                Type::deserialize(MapAccessDeserializer::new(map))
+            }
+        }
+
+        deserializer.deserialize_any(TypeVisitor)
+    }
+}
+
+/// Supported element types of SQL arrays.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+pub enum ElementType {
+    Bool,
+
+    SmallInt,
+    Int,
+    BigInt,
+
+    Float,
+    DoublePrecision,
+
+    Numeric,
+
+    Text,
+
+    Uuid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_char_types() {
+        //given
+        let legacy = r#"{"column":"c","raw_value":"126","type":"Char"}"#;
+        let current = r#"{"column":"c","raw_value":"A","type":{"Char":3}}"#;
+
+        //when
+        let legacy: Value = serde_json::from_str(legacy).unwrap();
+        let current: Value = serde_json::from_str(current).unwrap();
+
+        //then
+        assert_eq!(legacy.type_, Type::TinyInt);
+        assert_eq!(current.type_, Type::Char(Some(3)));
+        assert_eq!(
+            serde_json::to_string(&current.type_).unwrap(),
+            r#"{"Char":3}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&legacy.type_).unwrap(),
+            r#""TinyInt""#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_type_errors() {
+        //when
+        let unknown = serde_json::from_str::<Type>(r#""Varchar""#).unwrap_err();
+        let invalid_length = serde_json::from_str::<Type>(r#"{"Char":"3"}"#).unwrap_err();
+        let invalid_type = serde_json::from_str::<Type>("3").unwrap_err();
+
+        //then
+        assert!(unknown
+            .to_string()
+            .starts_with("unknown variant `Varchar`, expected one of `Bool`"));
+        assert!(invalid_length
+            .to_string()
+            .starts_with("invalid type: string \"3\", expected u32"));
+        assert!(invalid_type.to_string().starts_with(
+            "invalid type: integer `3`, expected type name or map with a single type name key"
+        ));
+    }
 }
@@ -0,0 +1,62 @@
+use std::cmp::Ordering;
+
+use serde_json::{Number, Value};
+
+/// Numbers are equal by value, `1` equals `1.0`, other values by their JSON equality.
+pub(crate) fn equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => {
+            order_numbers(left, right) == Some(Ordering::Equal)
+        }
+        _ => left == right,
+    }
+}
+
+/// Orders numbers and strings, `None` for other values.
+///
+/// Shared by the expressions and the JSONPath filters, so `qty > 1` and `$[?(@.qty > 1)]` agree.
+pub(crate) fn order(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => order_numbers(left, right),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => None,
+    }
+}
+
+/// Integers are compared exactly, the precision of `f64` is only used for floats.
+fn order_numbers(left: &Number, right: &Number) -> Option<Ordering> {
+    match (left.as_i64(), right.as_i64()) {
+        (Some(left), Some(right)) => Some(left.cmp(&right)),
+        _ => left.as_f64()?.partial_cmp(&right.as_f64()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_equals() {
+        assert!(equals(&json!(1), &json!(1.0)));
+        assert!(equals(&json!("a"), &json!("a")));
+        assert!(equals(&json!(null), &json!(null)));
+        assert!(!equals(
+            &json!(9007199254740993i64),
+            &json!(9007199254740992i64)
+        ));
+        assert!(!equals(&json!(1), &json!("1")));
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(order(&json!(2), &json!(1.5)), Some(Ordering::Greater));
+        assert_eq!(
+            order(&json!(9007199254740993i64), &json!(9007199254740992i64)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(order(&json!("a"), &json!("b")), Some(Ordering::Less));
+        assert_eq!(order(&json!(1), &json!("1")), None);
+        assert_eq!(order(&json!(null), &json!(null)), None);
+    }
+}
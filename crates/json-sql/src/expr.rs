@@ -0,0 +1,972 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+use eyre::eyre;
+use fluvio_smartmodule::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::compare;
+use crate::path::{JsonPath, RecordMetadata};
+
+/// Maximum nesting of the expression, deeper expressions are rejected.
+const MAX_DEPTH: usize = 64;
+
+/// Expression computing the column value from the record, e.g. `concat(first, ' ', last)`.
+///
+/// Operands are literals (`'text'`, `1.5`, `true`, `null`) and JSON paths (`price`, `$.a.b`,
+/// `@.sku`, `@key`), missing values are `null`, `exists(path)` tells present `null` values apart.
+/// Arithmetic and ordering with `null` yield `null`.
+/// The expression only reads the record, evaluation has no side effects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    source: String,
+    node: Node,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Literal(Value),
+    Path(JsonPath),
+    Unary(UnaryOp, Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Function {
+    Concat,
+    Lower,
+    Upper,
+    Trim,
+    Length,
+    Coalesce,
+    If,
+    Abs,
+    Round,
+    Exists,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let node = Parser::new(source)
+            .expression()
+            .map_err(|err| eyre!("invalid expression `{source}`: {err}"))?;
+        Ok(Self {
+            source: source.to_string(),
+            node,
+        })
+    }
+
+    /// Evaluates the expression, relative paths select from the current element.
+    pub fn evaluate(
+        &self,
+        record: &Value,
+        current: &Value,
+        metadata: &RecordMetadata,
+    ) -> Result<Value> {
+        let scope = Scope {
+            record,
+            current,
+            metadata,
+        };
+        scope
+            .eval(&self.node)
+            .map_err(|err| eyre!("failed to evaluate `{}`: {}", self.source, err))
+    }
+
+    /// Evaluates the condition, `null` is false.
+    pub fn matches(&self, record: &Value, metadata: &RecordMetadata) -> Result<bool> {
+        let value = self.evaluate(record, record, metadata)?;
+        truthy(value).map_err(|err| eyre!("condition `{}`: {}", self.source, err))
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Expression {}
+
+impl TryFrom<String> for Expression {
+    type Error = eyre::Report;
+
+    fn try_from(source: String) -> Result<Self> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.source
+    }
+}
+
+impl BinaryOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Or => "||",
+            Self::And => "&&",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+        }
+    }
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        let function = match name {
+            "concat" => Self::Concat,
+            "lower" => Self::Lower,
+            "upper" => Self::Upper,
+            "trim" => Self::Trim,
+            "length" => Self::Length,
+            "coalesce" => Self::Coalesce,
+            "if" => Self::If,
+            "abs" => Self::Abs,
+            "round" => Self::Round,
+            "exists" => Self::Exists,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Concat => "concat",
+            Self::Lower => "lower",
+            Self::Upper => "upper",
+            Self::Trim => "trim",
+            Self::Length => "length",
+            Self::Coalesce => "coalesce",
+            Self::If => "if",
+            Self::Abs => "abs",
+            Self::Round => "round",
+            Self::Exists => "exists",
+        }
+    }
+
+    /// Accepted number of arguments, `None` for any positive number.
+    fn arity(self) -> Option<usize> {
+        match self {
+            Self::Concat | Self::Coalesce => None,
+            Self::If => Some(3),
+            Self::Lower
+            | Self::Upper
+            | Self::Trim
+            | Self::Length
+            | Self::Abs
+            | Self::Round
+            | Self::Exists => Some(1),
+        }
+    }
+}
+
+type EvalResult<T> = std::result::Result<T, String>;
+
+struct Scope<'a> {
+    record: &'a Value,
+    current: &'a Value,
+    metadata: &'a RecordMetadata,
+}
+
+impl Scope<'_> {
+    fn eval(&self, node: &Node) -> EvalResult<Value> {
+        match node {
+            Node::Literal(value) => Ok(value.clone()),
+            Node::Path(path) => Ok(path
+                .select_in(self.record, self.current, self.metadata)
+                .map(Cow::into_owned)
+                .unwrap_or(Value::Null)),
+            Node::Unary(UnaryOp::Not, operand) => Ok(Value::Bool(!truthy(self.eval(operand)?)?)),
+            Node::Unary(UnaryOp::Neg, operand) => match self.eval(operand)? {
+                Value::Null => Ok(Value::Null),
+                Value::Number(number) => match number.as_i64().and_then(i64::checked_neg) {
+                    Some(negated) => Ok(negated.into()),
+                    None => float_value(-as_f64(&number)),
+                },
+                other => Err(format!("operator - expects a number, got {other}")),
+            },
+            Node::Binary(BinaryOp::And, left, right) => {
+                let result = truthy(self.eval(left)?)? && truthy(self.eval(right)?)?;
+                Ok(Value::Bool(result))
+            }
+            Node::Binary(BinaryOp::Or, left, right) => {
+                let result = truthy(self.eval(left)?)? || truthy(self.eval(right)?)?;
+                Ok(Value::Bool(result))
+            }
+            Node::Binary(op, left, right) => {
+                let (left, right) = (self.eval(left)?, self.eval(right)?);
+                match op {
+                    BinaryOp::Eq => Ok(Value::Bool(compare::equals(&left, &right))),
+                    BinaryOp::Ne => Ok(Value::Bool(!compare::equals(&left, &right))),
+                    BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                        let Some(ordering) = compare(*op, &left, &right)? else {
+                            return Ok(Value::Null);
+                        };
+                        let result = match op {
+                            BinaryOp::Lt => ordering.is_lt(),
+                            BinaryOp::Le => ordering.is_le(),
+                            BinaryOp::Gt => ordering.is_gt(),
+                            _ => ordering.is_ge(),
+                        };
+                        Ok(Value::Bool(result))
+                    }
+                    _ => arithmetic(*op, left, right),
+                }
+            }
+            Node::Call(function, args) => self.call(*function, args),
+        }
+    }
+
+    fn call(&self, function: Function, args: &[Node]) -> EvalResult<Value> {
+        match function {
+            Function::Concat => {
+                let mut text = String::new();
+                for arg in args {
+                    match self.eval(arg)? {
+                        Value::Null => {}
+                        Value::String(part) => text.push_str(&part),
+                        other => text.push_str(&other.to_string()),
+                    }
+                }
+                Ok(Value::String(text))
+            }
+            Function::Coalesce => {
+                for arg in args {
+                    let value = self.eval(arg)?;
+                    if !value.is_null() {
+                        return Ok(value);
+                    }
+                }
+                Ok(Value::Null)
+            }
+            Function::Exists => match &args[0] {
+                Node::Path(path) => Ok(Value::Bool(
+                    path.select_in(self.record, self.current, self.metadata)
+                        .is_some(),
+                )),
+                _ => Err("exists expects a path".to_string()),
+            },
+            Function::If => {
+                if truthy(self.eval(&args[0])?)? {
+                    self.eval(&args[1])
+                } else {
+                    self.eval(&args[2])
+                }
+            }
+            Function::Lower | Function::Upper | Function::Trim => {
+                let text = match self.eval(&args[0])? {
+                    Value::Null => return Ok(Value::Null),
+                    Value::String(text) => text,
+                    other => {
+                        return Err(format!("{} expects a string, got {other}", function.name()))
+                    }
+                };
+                let result = match function {
+                    Function::Lower => text.to_lowercase(),
+                    Function::Upper => text.to_uppercase(),
+                    _ => text.trim().to_string(),
+                };
+                Ok(Value::String(result))
+            }
+            Function::Length => match self.eval(&args[0])? {
+                Value::Null => Ok(Value::Null),
+                Value::String(text) => Ok(text.chars().count().into()),
+                Value::Array(items) => Ok(items.len().into()),
+                Value::Object(members) => Ok(members.len().into()),
+                other => Err(format!("length expects a string or array, got {other}")),
+            },
+            Function::Abs | Function::Round => match self.eval(&args[0])? {
+                Value::Null => Ok(Value::Null),
+                Value::Number(number) if number.is_i64() || number.is_u64() => {
+                    match (function, number.as_i64()) {
+                        (Function::Abs, Some(int)) => match int.checked_abs() {
+                            Some(abs) => Ok(abs.into()),
+                            None => float_value(as_f64(&number).abs()),
+                        },
+                        _ => Ok(Value::Number(number)),
+                    }
+                }
+                Value::Number(number) => match function {
+                    Function::Abs => float_value(as_f64(&number).abs()),
+                    _ => float_value(as_f64(&number).round()),
+                },
+                other => Err(format!("{} expects a number, got {other}", function.name())),
+            },
+        }
+    }
+}
+
+/// Boolean value of the condition, `null` is false.
+fn truthy(value: Value) -> EvalResult<bool> {
+    match value {
+        Value::Bool(value) => Ok(value),
+        Value::Null => Ok(false),
+        other => Err(format!("expected a boolean, got {other}")),
+    }
+}
+
+/// Orders numbers and strings, `None` if either value is `null`.
+fn compare(op: BinaryOp, left: &Value, right: &Value) -> EvalResult<Option<Ordering>> {
+    match (left, right) {
+        (Value::Null, _) | (_, Value::Null) => Ok(None),
+        (Value::Number(_), Value::Number(_)) | (Value::String(_), Value::String(_)) => {
+            Ok(compare::order(left, right))
+        }
+        (left, right) => Err(format!(
+            "operator {} can't compare {left} and {right}",
+            op.as_str()
+        )),
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: Value, right: Value) -> EvalResult<Value> {
+    let (left, right) = match (left, right) {
+        (Value::Null, _) | (_, Value::Null) => return Ok(Value::Null),
+        (Value::Number(left), Value::Number(right)) => (left, right),
+        (left, right) => {
+            return Err(format!(
+                "operator {} expects numbers, got {left} and {right}",
+                op.as_str()
+            ))
+        }
+    };
+    if let (Some(left), Some(right)) = (left.as_i64(), right.as_i64()) {
+        if matches!(op, BinaryOp::Div | BinaryOp::Rem) && right == 0 {
+            return Err("division by zero".to_string());
+        }
+        let result = match op {
+            BinaryOp::Add => left.checked_add(right),
+            BinaryOp::Sub => left.checked_sub(right),
+            BinaryOp::Mul => left.checked_mul(right),
+            BinaryOp::Rem => left.checked_rem(right),
+            _ => None,
+        };
+        if let Some(result) = result {
+            return Ok(result.into());
+        }
+    }
+    let (left, right) = (as_f64(&left), as_f64(&right));
+    if matches!(op, BinaryOp::Div | BinaryOp::Rem) && right == 0.0 {
+        return Err("division by zero".to_string());
+    }
+    let result = match op {
+        BinaryOp::Add => left + right,
+        BinaryOp::Sub => left - right,
+        BinaryOp::Mul => left * right,
+        BinaryOp::Div => left / right,
+        _ => left % right,
+    };
+    float_value(result)
+}
+
+fn as_f64(number: &Number) -> f64 {
+    number.as_f64().unwrap_or(f64::NAN)
+}
+
+/// Number of the float, integral values within the exact range become integers.
+fn float_value(value: f64) -> EvalResult<Value> {
+    const EXACT: f64 = (1u64 << 53) as f64;
+    if value.fract() == 0.0 && value.abs() <= EXACT {
+        return Ok((value as i64).into());
+    }
+    Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or_else(|| "result is not a finite number".to_string())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Nesting of the parser calls, bounds the recursion on parentheses
+    depth: usize,
+    /// Height of the last parsed node, bounds the tree including operator chains like `1+1+1`
+    height: usize,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            depth: 0,
+            height: 0,
+        }
+    }
+
+    fn expression(&mut self) -> ParseResult<Node> {
+        let node = self.or()?;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) => Err(format!("unexpected `{c}` at {}", self.pos)),
+            None => Ok(node),
+        }
+    }
+
+    fn or(&mut self) -> ParseResult<Node> {
+        let mut left = self.and()?;
+        while self.eat_operator("||") {
+            let left_height = self.height;
+            let right = self.and()?;
+            self.nest(left_height.max(self.height))?;
+            left = Node::Binary(BinaryOp::Or, left.into(), right.into());
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> ParseResult<Node> {
+        let mut left = self.comparison()?;
+        while self.eat_operator("&&") {
+            let left_height = self.height;
+            let right = self.comparison()?;
+            self.nest(left_height.max(self.height))?;
+            left = Node::Binary(BinaryOp::And, left.into(), right.into());
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> ParseResult<Node> {
+        let left = self.additive()?;
+        let op = if self.eat_operator("==") {
+            BinaryOp::Eq
+        } else if self.eat_operator("!=") {
+            BinaryOp::Ne
+        } else if self.eat_operator("<=") {
+            BinaryOp::Le
+        } else if self.eat_operator(">=") {
+            BinaryOp::Ge
+        } else if self.eat_operator("<") {
+            BinaryOp::Lt
+        } else if self.eat_operator(">") {
+            BinaryOp::Gt
+        } else {
+            return Ok(left);
+        };
+        let left_height = self.height;
+        let right = self.additive()?;
+        self.nest(left_height.max(self.height))?;
+        Ok(Node::Binary(op, left.into(), right.into()))
+    }
+
+    fn additive(&mut self) -> ParseResult<Node> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = if self.eat_operator("+") {
+                BinaryOp::Add
+            } else if self.eat_operator("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(left);
+            };
+            let left_height = self.height;
+            let right = self.multiplicative()?;
+            self.nest(left_height.max(self.height))?;
+            left = Node::Binary(op, left.into(), right.into());
+        }
+    }
+
+    fn multiplicative(&mut self) -> ParseResult<Node> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_operator("*") {
+                BinaryOp::Mul
+            } else if self.eat_operator("/") {
+                BinaryOp::Div
+            } else if self.eat_operator("%") {
+                BinaryOp::Rem
+            } else {
+                return Ok(left);
+            };
+            let left_height = self.height;
+            let right = self.unary()?;
+            self.nest(left_height.max(self.height))?;
+            left = Node::Binary(op, left.into(), right.into());
+        }
+    }
+
+    fn unary(&mut self) -> ParseResult<Node> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!("nested deeper than {MAX_DEPTH} levels"));
+        }
+        let node = if self.eat_operator("!") {
+            let operand = self.unary()?;
+            self.nest(self.height)?;
+            Node::Unary(UnaryOp::Not, operand.into())
+        } else if self.eat_operator("-") {
+            let operand = self.unary()?;
+            self.nest(self.height)?;
+            Node::Unary(UnaryOp::Neg, operand.into())
+        } else {
+            self.primary()?
+        };
+        self.depth -= 1;
+        Ok(node)
+    }
+
+    /// Sets the height of the node parsed over its tallest operand.
+    fn nest(&mut self, operand_height: usize) -> ParseResult<()> {
+        self.height = operand_height + 1;
+        if self.height > MAX_DEPTH {
+            return Err(format!("nested deeper than {MAX_DEPTH} levels"));
+        }
+        Ok(())
+    }
+
+    fn primary(&mut self) -> ParseResult<Node> {
+        // literals and paths are leaves, parentheses and calls set the height of their nodes
+        self.height = 1;
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let node = self.or()?;
+                self.skip_whitespace();
+                self.expect(')')?;
+                Ok(node)
+            }
+            Some(quote @ ('\'' | '"')) => self.string(quote),
+            Some(c) if c.is_ascii_digit() => self.number(),
+            Some('$' | '@') => self.path(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                let name = self.identifier();
+                self.skip_whitespace();
+                if self.peek() == Some('(') {
+                    return self.call(&name);
+                }
+                match name.as_str() {
+                    "true" => Ok(Node::Literal(Value::Bool(true))),
+                    "false" => Ok(Node::Literal(Value::Bool(false))),
+                    "null" => Ok(Node::Literal(Value::Null)),
+                    _ => {
+                        self.pos = start;
+                        self.path()
+                    }
+                }
+            }
+            Some(c) => Err(format!("unexpected `{c}` at {}", self.pos)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    fn call(&mut self, name: &str) -> ParseResult<Node> {
+        let function =
+            Function::from_name(name).ok_or_else(|| format!("unknown function `{name}`"))?;
+        self.expect('(')?;
+        let mut args = Vec::new();
+        let mut height = 0;
+        self.skip_whitespace();
+        if !self.eat(')') {
+            loop {
+                args.push(self.or()?);
+                height = height.max(self.height);
+                self.skip_whitespace();
+                if self.eat(')') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        let valid = match function.arity() {
+            Some(arity) => args.len() == arity,
+            None => !args.is_empty(),
+        };
+        if !valid {
+            return Err(format!("wrong number of arguments of `{name}`"));
+        }
+        if matches!(function, Function::Exists) && !matches!(args[0], Node::Path(_)) {
+            return Err("`exists` expects a path".to_string());
+        }
+        self.nest(height)?;
+        Ok(Node::Call(function, args))
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// JSON path operand, ends at the first character that is not part of a member name
+    /// outside of brackets.
+    fn path(&mut self) -> ParseResult<Node> {
+        let start = self.pos;
+        let mut brackets = 0;
+        let mut quote = None;
+        while let Some(c) = self.peek() {
+            match (quote, c) {
+                (Some(_), '\\') => self.pos += 1,
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"') if brackets > 0 => quote = Some(c),
+                (None, '[') => brackets += 1,
+                (None, ']') if brackets > 0 => brackets -= 1,
+                (None, c)
+                    if brackets > 0
+                        || c.is_alphanumeric()
+                        || matches!(c, '_' | '.' | '$' | '@') => {}
+                // wildcard member, otherwise the multiplication
+                (None, '*') if self.chars[self.pos - 1] == '.' => {}
+                (None, _) => break,
+            }
+            self.pos += 1;
+        }
+        let source: String = self.chars[start..self.pos].iter().collect();
+        JsonPath::parse(&source)
+            .map(Node::Path)
+            .map_err(|err| err.to_string())
+    }
+
+    fn string(&mut self, quote: char) -> ParseResult<Node> {
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                None => return Err("unterminated string".to_string()),
+                Some('\\') => match self.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(c) => text.push(c),
+                    None => return Err("unterminated string".to_string()),
+                },
+                Some(c) if c == quote => return Ok(Node::Literal(Value::String(text))),
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> ParseResult<Node> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E')
+        {
+            self.pos += 1;
+            if matches!(self.chars[self.pos - 1], 'e' | 'E')
+                && matches!(self.peek(), Some('+' | '-'))
+            {
+                self.pos += 1;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if let Ok(int) = text.parse::<i64>() {
+            return Ok(Node::Literal(int.into()));
+        }
+        text.parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(|number| Node::Literal(Value::Number(number)))
+            .ok_or_else(|| format!("invalid number `{text}`"))
+    }
+
+    fn eat_operator(&mut self, operator: &str) -> bool {
+        self.skip_whitespace();
+        let end = self.pos + operator.chars().count();
+        let matched = end <= self.chars.len()
+            && self.chars[self.pos..end]
+                .iter()
+                .copied()
+                .eq(operator.chars());
+        // `<` and `>` are not prefixes of `<=` and `>=`, `!` is not a prefix of `!=`
+        let prefix_of_longer =
+            matches!(operator, "<" | ">" | "!") && self.chars.get(end) == Some(&'=');
+        if matched && !prefix_of_longer {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected `{c}` at {}", self.pos))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn evaluate(expression: &str, input: &Value) -> Result<Value> {
+        Expression::parse(expression)?.evaluate(input, input, &RecordMetadata::default())
+    }
+
+    #[test]
+    fn test_string_functions() {
+        // given
+        let input = json!({
+            "first": "Ada",
+            "last": "Lovelace",
+            "email": "  Ada@Example.COM ",
+            "tags": ["a", "b"]
+        });
+
+        // when
+        let full_name = evaluate("concat(first, ' ', last)", &input).unwrap();
+        let email = evaluate("lower(trim(email))", &input).unwrap();
+        let upper = evaluate("upper($.first)", &input).unwrap();
+        let length = evaluate("length(tags) + length(first)", &input).unwrap();
+        let with_missing = evaluate(r#"concat(first, "-", middle, 1, true)"#, &input).unwrap();
+
+        // then
+        assert_eq!(full_name, json!("Ada Lovelace"));
+        assert_eq!(email, json!("ada@example.com"));
+        assert_eq!(upper, json!("ADA"));
+        assert_eq!(length, json!(5));
+        assert_eq!(with_missing, json!("Ada-1true"));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // given
+        let input = json!({"price": 2.5, "qty": 4, "big": i64::MAX, "order": {"discount": 1}});
+
+        // when
+        let total = evaluate("price * qty - order.discount", &input).unwrap();
+        let precedence = evaluate("1 + 2 * 3 % 4 - -(1)", &input).unwrap();
+        let division = evaluate("qty / 8", &input).unwrap();
+        let overflow = evaluate("big + 1", &input).unwrap();
+        let missing = evaluate("price * missing", &input).unwrap();
+        let by_zero = evaluate("qty / 0", &input);
+        let not_number = evaluate("qty + 'a'", &input);
+
+        // then
+        assert_eq!(total, json!(9));
+        assert_eq!(precedence, json!(4));
+        assert_eq!(division, json!(0.5));
+        assert_eq!(overflow, json!(9223372036854775808.0));
+        assert_eq!(missing, Value::Null);
+        assert!(by_zero
+            .unwrap_err()
+            .to_string()
+            .contains("division by zero"));
+        assert!(not_number
+            .unwrap_err()
+            .to_string()
+            .contains("operator + expects numbers"));
+    }
+
+    #[test]
+    fn test_conditions() {
+        // given
+        let input = json!({"status": "x", "a": null, "b": 2, "qty": 3});
+
+        // when
+        let flag = evaluate("if(status == 'x', 1, 0)", &input).unwrap();
+        let coalesce = evaluate("coalesce(a, missing, b)", &input).unwrap();
+        let logic = evaluate("qty >= 3 && !(status != 'x') || false", &input).unwrap();
+        let numbers = evaluate("b == 2.0 && 'a' < 'b'", &input).unwrap();
+        let null_ordering = evaluate("if(a > 1, 'yes', 'no')", &input).unwrap();
+        let not_boolean = evaluate("if(qty, 1, 0)", &input);
+
+        // then
+        assert_eq!(flag, json!(1));
+        assert_eq!(coalesce, json!(2));
+        assert_eq!(logic, json!(true));
+        assert_eq!(numbers, json!(true));
+        assert_eq!(null_ordering, json!("no"));
+        assert!(not_boolean
+            .unwrap_err()
+            .to_string()
+            .contains("expected a boolean"));
+    }
+
+    #[test]
+    fn test_conditions_agree_with_path_filters() {
+        // given
+        let big = 9007199254740993i64;
+        let items = json!([
+            {"qty": 2}, {"qty": 1.0}, {"qty": "3"}, {"qty": big}, {"qty": null}
+        ]);
+        let conditions = [
+            ("qty > 1", "[?(@.qty > 1)]"),
+            ("qty == 1", "[?(@.qty == 1)]"),
+            ("qty >= 9007199254740993", "[?(@.qty >= 9007199254740993)]"),
+            ("qty == 9007199254740992", "[?(@.qty == 9007199254740992)]"),
+        ];
+
+        for (condition, filter) in conditions {
+            // when
+            let matched: Vec<&Value> = items
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|item| {
+                    // comparing a string to a number is an error in the expressions
+                    item["qty"].is_number()
+                        && Expression::parse(condition)
+                            .unwrap()
+                            .matches(item, &RecordMetadata::default())
+                            .unwrap()
+                })
+                .collect();
+            let selected = JsonPath::parse(&format!("${filter}"))
+                .unwrap()
+                .select(&items, &RecordMetadata::default())
+                .map(Cow::into_owned);
+
+            // then
+            let expected = (!matched.is_empty()).then(|| json!(matched));
+            assert_eq!(selected, expected, "{condition}");
+        }
+    }
+
+    #[test]
+    fn test_exists() {
+        // given
+        let input = json!({"a": null, "b": {"c": 1}});
+
+        // when
+        let present_null = evaluate("exists(a)", &input).unwrap();
+        let nested = evaluate("exists($.b.c) && !exists(b.d)", &input).unwrap();
+        let missing = evaluate("exists(missing)", &input).unwrap();
+
+        // then
+        assert_eq!(present_null, json!(true));
+        assert_eq!(nested, json!(true));
+        assert_eq!(missing, json!(false));
+    }
+
+    #[test]
+    fn test_paths_and_metadata() {
+        // given
+        let input = json!({"items": [{"sku": "a", "qty": 2}, {"sku": "b", "qty": 0}], "a-b": 1});
+        let element = &input["items"][0];
+        let metadata = RecordMetadata {
+            key: Some("k1".to_string()),
+            ..Default::default()
+        };
+        let evaluate = |expression: &str| {
+            Expression::parse(expression)
+                .unwrap()
+                .evaluate(&input, element, &metadata)
+                .unwrap()
+        };
+
+        // when
+        let relative = evaluate("concat(@key, ':', @.sku)");
+        let bracket = evaluate("$['a-b'] * 10");
+        let filter = evaluate("length($.items[?(@.qty > 0)])");
+
+        // then
+        assert_eq!(relative, json!("k1:a"));
+        assert_eq!(bracket, json!(10));
+        assert_eq!(filter, json!(1));
+    }
+
+    #[test]
+    fn test_invalid_expression() {
+        assert!(Expression::parse("").is_err());
+        assert!(Expression::parse("1 +").is_err());
+        assert!(Expression::parse("concat(a, b").is_err());
+        assert!(Expression::parse("unknown(a)").is_err());
+        assert!(Expression::parse("lower(a, b)").is_err());
+        assert!(Expression::parse("coalesce()").is_err());
+        assert!(Expression::parse("'unterminated").is_err());
+        assert!(Expression::parse("a b").is_err());
+        assert!(Expression::parse(&"(".repeat(100)).is_err());
+        assert!(Expression::parse("exists('a')").is_err());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        // given
+        let chain = |count: usize, op: &str| format!("1{}", format!(" {op} 1").repeat(count));
+        let nested_chains = format!("({}){}", chain(40, "*"), " + 1".repeat(40));
+
+        // then
+        assert!(Expression::parse(&chain(MAX_DEPTH - 1, "+")).is_ok());
+        assert_eq!(
+            Expression::parse(&chain(MAX_DEPTH + 1, "+"))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "invalid expression `{}`: nested deeper than {MAX_DEPTH} levels",
+                chain(MAX_DEPTH + 1, "+")
+            )
+        );
+        assert!(Expression::parse(&chain(100_000, "-")).is_err());
+        assert!(Expression::parse(&chain(100, "||")).is_err());
+        assert!(Expression::parse(&nested_chains).is_err());
+        assert!(Expression::parse(&format!("abs({})", chain(MAX_DEPTH, "*"))).is_err());
+        assert!(Expression::parse(&"-".repeat(MAX_DEPTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{
+    DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeDelta, Utc,
+};
+use eyre::{eyre, ContextCompat};
+use fluvio_smartmodule::Result;
+
+use crate::mapping::{TimeFormat, ValueType};
+
+/// Canonical representations expected by the SQL sink.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+/// Parses the date and time value in the given format and renders it in the canonical
+/// representation of the column type. Values without an offset are considered UTC.
+pub(crate) fn normalize(raw: &str, format: &TimeFormat, type_: &ValueType) -> Result<String> {
+    match (type_, format) {
+        (ValueType::Date, TimeFormat::Pattern(pattern)) => {
+            if let Ok(date) = NaiveDate::parse_from_str(raw, pattern) {
+                return Ok(date.format(DATE_FORMAT).to_string());
+            }
+        }
+        (ValueType::Time, TimeFormat::Pattern(pattern)) => {
+            if let Ok(time) = NaiveTime::parse_from_str(raw, pattern) {
+                return Ok(time.format(TIME_FORMAT).to_string());
+            }
+        }
+        _ => {}
+    }
+
+    let date_time = parse_date_time(raw, format)?;
+    let normalized = match type_ {
+        ValueType::Timestamp => date_time.naive_utc().format(TIMESTAMP_FORMAT).to_string(),
+        ValueType::TimestampTz => date_time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ValueType::Date => date_time.date_naive().format(DATE_FORMAT).to_string(),
+        ValueType::Time => date_time.time().format(TIME_FORMAT).to_string(),
+        other => return Err(eyre!("format is not supported for {:?} type", other)),
+    };
+    Ok(normalized)
+}
+
+fn parse_date_time(raw: &str, format: &TimeFormat) -> Result<DateTime<Utc>> {
+    let date_time = match format {
+        TimeFormat::Rfc3339 => DateTime::parse_from_rfc3339(raw)?.with_timezone(&Utc),
+        TimeFormat::EpochSeconds => match raw.parse::<i64>() {
+            Ok(secs) => DateTime::from_timestamp(secs, 0),
+            Err(_) => {
+                let secs = raw.parse::<f64>()?;
+                if !secs.is_finite() {
+                    return Err(eyre!("epoch seconds is not a number: {}", raw));
+                }
+                // the fraction is positive for negative values as well, -1.5 is -2 + 0.5
+                let whole = secs.floor();
+                let nanos = ((secs - whole) * 1_000_000_000f64).round() as i64;
+                DateTime::from_timestamp(whole as i64, 0).and_then(|date_time| {
+                    date_time.checked_add_signed(TimeDelta::nanoseconds(nanos))
+                })
+            }
+        }
+        .wrap_err("epoch seconds out of range")?,
+        TimeFormat::EpochMillis => DateTime::from_timestamp_millis(raw.parse()?)
+            .wrap_err("epoch milliseconds out of range")?,
+        TimeFormat::EpochMicros => DateTime::from_timestamp_micros(raw.parse()?)
+            .wrap_err("epoch microseconds out of range")?,
+        TimeFormat::Pattern(pattern) => match DateTime::<FixedOffset>::parse_from_str(raw, pattern)
+        {
+            Ok(date_time) => date_time.with_timezone(&Utc),
+            Err(_) => NaiveDateTime::parse_from_str(raw, pattern)?.and_utc(),
+        },
+    };
+    Ok(date_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        //given
+        let format = TimeFormat::Rfc3339;
+
+        //when
+        let timestamp = normalize("2024-01-01T12:00:00+02:00", &format, &ValueType::Timestamp);
+        let timestamp_tz = normalize(
+            "2024-01-01T12:00:00+02:00",
+            &format,
+            &ValueType::TimestampTz,
+        );
+        let date = normalize("2024-01-01T00:30:00+02:00", &format, &ValueType::Date);
+
+        //then
+        assert_eq!(timestamp.unwrap(), "2024-01-01 10:00:00");
+        assert_eq!(timestamp_tz.unwrap(), "2024-01-01T10:00:00Z");
+        assert_eq!(date.unwrap(), "2023-12-31");
+    }
+
+    #[test]
+    fn test_epoch() {
+        //when
+        let secs = normalize(
+            "1704103200",
+            &TimeFormat::EpochSeconds,
+            &ValueType::Timestamp,
+        );
+        let fractional_secs = normalize(
+            "1704103200.5",
+            &TimeFormat::EpochSeconds,
+            &ValueType::Timestamp,
+        );
+        let millis = normalize(
+            "1704103200123",
+            &TimeFormat::EpochMillis,
+            &ValueType::TimestampTz,
+        );
+        let micros = normalize(
+            "1704103200123456",
+            &TimeFormat::EpochMicros,
+            &ValueType::Timestamp,
+        );
+
+        let negative_secs = normalize("-1.5", &TimeFormat::EpochSeconds, &ValueType::Timestamp);
+
+        //then
+        assert_eq!(secs.unwrap(), "2024-01-01 10:00:00");
+        assert_eq!(fractional_secs.unwrap(), "2024-01-01 10:00:00.500");
+        assert_eq!(negative_secs.unwrap(), "1969-12-31 23:59:58.500");
+        assert_eq!(millis.unwrap(), "2024-01-01T10:00:00.123Z");
+        assert_eq!(micros.unwrap(), "2024-01-01 10:00:00.123456");
+    }
+
+    #[test]
+    fn test_pattern() {
+        //when
+        let naive = normalize(
+            "01/01/2024 10:00",
+            &TimeFormat::Pattern("%d/%m/%Y %H:%M".to_string()),
+            &ValueType::TimestampTz,
+        );
+        let with_offset = normalize(
+            "01/01/2024 12:00 +0200",
+            &TimeFormat::Pattern("%d/%m/%Y %H:%M %z".to_string()),
+            &ValueType::Timestamp,
+        );
+        let date = normalize(
+            "01/02/2024",
+            &TimeFormat::Pattern("%d/%m/%Y".to_string()),
+            &ValueType::Date,
+        );
+        let time = normalize(
+            "10h05",
+            &TimeFormat::Pattern("%Hh%M".to_string()),
+            &ValueType::Time,
+        );
+
+        //then
+        assert_eq!(naive.unwrap(), "2024-01-01T10:00:00Z");
+        assert_eq!(with_offset.unwrap(), "2024-01-01 10:00:00");
+        assert_eq!(date.unwrap(), "2024-02-01");
+        assert_eq!(time.unwrap(), "10:05:00");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(normalize("yesterday", &TimeFormat::Rfc3339, &ValueType::Timestamp).is_err());
+        assert!(normalize("1.5e400", &TimeFormat::EpochMillis, &ValueType::Timestamp).is_err());
+        assert!(normalize("1704103200", &TimeFormat::EpochSeconds, &ValueType::Text).is_err());
+    }
+}
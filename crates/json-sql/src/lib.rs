@@ -1,22 +1,40 @@
+mod compare;
+mod expr;
+mod format;
 mod mapping;
-mod pointer;
+mod path;
 mod transform;
 
 use once_cell::sync::OnceCell;
 
 use crate::mapping::Mapping;
+use crate::path::RecordMetadata;
 use eyre::ContextCompat;
 use fluvio_smartmodule::{
-    dataplane::smartmodule::SmartModuleExtraParams, smartmodule, Record, RecordData, Result,
+    dataplane::smartmodule::SmartModuleExtraParams, smartmodule, RecordData, Result,
+    SmartModuleRecord,
 };
 
 static MAPPING: OnceCell<Mapping> = OnceCell::new();
+// SmartModule records don't carry the partition, the connector configures it
+static PARTITION: OnceCell<i32> = OnceCell::new();
 
 #[smartmodule(init)]
 fn init(params: SmartModuleExtraParams) -> Result<()> {
+    if let Some(raw_partition) = params.get("partition") {
+        let partition = raw_partition
+            .parse()
+            .map_err(|_| eyre::eyre!("invalid json-sql partition: {raw_partition}"))?;
+        PARTITION
+            .set(partition)
+            .expect("partition is already initialized");
+    }
     if let Some(raw_mapping) = params.get("mapping") {
-        match serde_json::from_str(raw_mapping) {
+        match serde_json::from_str::<Mapping>(raw_mapping) {
             Ok(mapping) => {
+                mapping
+                    .validate()
+                    .map_err(|err| eyre::eyre!("invalid json-sql mapping: {err}"))?;
                 MAPPING
                     .set(mapping)
                     .expect("mapping is already initialized");
@@ -32,15 +50,24 @@ fn init(params: SmartModuleExtraParams) -> Result<()> {
     }
 }
 
-#[smartmodule(map)]
-pub fn map(record: &Record) -> Result<(Option<RecordData>, RecordData)> {
+#[smartmodule(array_map)]
+pub fn array_map(record: &SmartModuleRecord) -> Result<Vec<(Option<RecordData>, RecordData)>> {
     let mapping = MAPPING
         .get()
         .wrap_err("json-sql mapping is not initialized")?;
 
     let key = record.key.clone();
-    let record = serde_json::from_slice(record.value.as_ref())?;
-    let transformed = transform::transform(record, mapping)?;
-
-    Ok((key, serde_json::to_vec(&transformed)?.into()))
+    let metadata = RecordMetadata {
+        // binary keys are missing instead of replacing the invalid bytes
+        key: key
+            .as_ref()
+            .and_then(|key| String::from_utf8(key.as_ref().to_vec()).ok()),
+        offset: Some(record.offset()),
+        partition: PARTITION.get().copied(),
+        timestamp: Some(record.timestamp()).filter(|timestamp| *timestamp >= 0),
+    };
+    transform::transform_value(record.value.as_ref(), &metadata, mapping)?
+        .iter()
+        .map(|operation| Ok((key.clone(), serde_json::to_vec(operation)?.into())))
+        .collect()
 }
@@ -1,21 +1,275 @@
-use fluvio_model_sql::Type;
+use crate::expr::Expression;
+use crate::path::JsonPath;
+use eyre::eyre;
+use fluvio_model_sql::{ElementType, Type};
 use serde::de::Error;
 use serde::Serialize;
 use serde::{de, Deserialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Mapping {
+    // not used when operation is call
+    #[serde(default)]
     pub table: String,
     #[serde(default = "default_op")]
     pub operation: Operation,
-    // only used when operation is upsert
+    // operation of the record selected by its value, takes precedence over the operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_from: Option<OperationFrom>,
+    // only used when operation is upsert or delete
     #[serde(default)]
     pub unique_columns: Vec<String>,
-    #[serde(alias = "map-columns")]
+    // only used when operation is call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routine: Option<Routine>,
+    // array of the record turned into one operation per element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explode: Option<JsonPath>,
+    #[serde(default, alias = "map-columns")]
     pub columns: HashMap<String, Column>,
+    // mappings of several tables applied to the same record instead of the columns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<Mapping>,
+    // operations produced from the record are applied in one transaction
+    #[serde(default)]
+    pub transaction: bool,
+    // value selecting the mapping of the record from the routes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<JsonPath>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub routes: HashMap<String, Mapping>,
+    // used when no route matches the discriminator value
+    #[serde(default)]
+    pub fallback: Fallback,
+    // change data capture envelope, the operation is taken from the change event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cdc: Option<Cdc>,
+    // records not matching the condition produce no operations
+    #[serde(default, alias = "where", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Expression>,
+}
+
+impl Mapping {
+    /// Checks the mapping and the nested ones when it is loaded, instead of failing every record.
+    pub fn validate(&self) -> eyre::Result<()> {
+        let modes = [
+            ("discriminator", self.discriminator.is_some()),
+            ("tables", !self.tables.is_empty()),
+            ("cdc", self.cdc.is_some()),
+            ("explode", self.explode.is_some()),
+        ];
+        let mut used = modes.iter().filter(|(_, used)| *used).map(|(name, _)| name);
+        if let (Some(first), Some(second)) = (used.next(), used.next()) {
+            return Err(eyre!("{} and {} can't be used together", first, second));
+        }
+
+        self.validate_routing()?;
+        if self.discriminator.is_some() || !self.tables.is_empty() {
+            self.validate_nesting()?;
+        } else {
+            self.validate_columns()?;
+        }
+        if let Some(cdc) = &self.cdc {
+            self.validate_cdc(cdc)?;
+        }
+        if self.may_call() {
+            self.validate_call()?;
+        }
+        for table in self.tables.iter() {
+            table.validate()?;
+        }
+        for routed in self.routes.values() {
+            routed.validate()?;
+        }
+        if let Fallback::Mapping(fallback) = &self.fallback {
+            fallback.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Routes and fallback are only looked up by the discriminator value.
+    fn validate_routing(&self) -> eyre::Result<()> {
+        if self.discriminator.is_some() {
+            if self.routes.is_empty() {
+                return Err(eyre!("routes can't be empty when using discriminator"));
+            }
+            return Ok(());
+        }
+        if !self.routes.is_empty() {
+            return Err(eyre!("routes can't be used without discriminator"));
+        }
+        if self.fallback != Fallback::Error {
+            return Err(eyre!("fallback can't be used without discriminator"));
+        }
+        Ok(())
+    }
+
+    /// Whether the mapping or any of the nested ones reads change events.
+    pub fn uses_cdc(&self) -> bool {
+        self.cdc.is_some()
+            || self.tables.iter().any(Mapping::uses_cdc)
+            || self.routes.values().any(Mapping::uses_cdc)
+            || matches!(&self.fallback, Fallback::Mapping(fallback) if fallback.uses_cdc())
+    }
+
+    /// The nested mappings produce the operations, the own table and columns would be ignored.
+    fn validate_nesting(&self) -> eyre::Result<()> {
+        let ignored = [
+            ("table", !self.table.is_empty()),
+            ("map-columns", !self.columns.is_empty()),
+            ("unique-columns", !self.unique_columns.is_empty()),
+            ("routine", self.routine.is_some()),
+            ("operation-from", self.operation_from.is_some()),
+        ];
+        let nesting = if self.discriminator.is_some() {
+            "discriminator"
+        } else {
+            "tables"
+        };
+        match ignored.iter().find(|(_, used)| *used) {
+            Some((name, _)) => Err(eyre!("{} can't be used with {}", name, nesting)),
+            None => Ok(()),
+        }
+    }
+
+    fn validate_columns(&self) -> eyre::Result<()> {
+        if self.columns.is_empty() {
+            return Err(eyre!("map-columns can't be empty"));
+        }
+        if self.table.is_empty() && (self.cdc.is_some() || self.may_write_table()) {
+            return Err(eyre!("table can't be empty"));
+        }
+        for (operation, name) in [(Operation::Upsert, "upsert"), (Operation::Delete, "delete")] {
+            if self.unique_columns.is_empty() && self.may_use(&operation) {
+                return Err(eyre!("unique-columns can't be empty when doing {}", name));
+            }
+        }
+        if self.may_use(&Operation::Delete) {
+            if let Some(column) = self
+                .unique_columns
+                .iter()
+                .find(|column| !self.columns.contains_key(*column))
+            {
+                return Err(eyre!("unique column is not mapped: {}", column));
+            }
+        }
+        for (name, column) in self.columns.iter() {
+            if let Some(generator @ (Generator::Now | Generator::RecordTimestamp)) =
+                column.value.generate
+            {
+                if !column.value.type_.holds_time() {
+                    return Err(eyre!(
+                        "{:?} can't be generated for column {} of type {:?}",
+                        generator,
+                        name,
+                        column.value.type_
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The operations are taken from the change events.
+    fn validate_cdc(&self, cdc: &Cdc) -> eyre::Result<()> {
+        if let Some((name, _)) = [
+            ("operation-from", self.operation_from.is_some()),
+            ("routine", self.routine.is_some()),
+            ("unique-columns", !self.unique_columns.is_empty()),
+        ]
+        .iter()
+        .find(|(_, used)| *used)
+        {
+            return Err(eyre!("{} can't be used with cdc", name));
+        }
+        if cdc.primary_key.is_empty() {
+            return Err(eyre!("primary-key can't be empty in cdc mode"));
+        }
+        match cdc
+            .primary_key
+            .iter()
+            .find(|name| !self.columns.contains_key(*name))
+        {
+            Some(name) => Err(eyre!("primary key column is not mapped: {}", name)),
+            None => Ok(()),
+        }
+    }
+
+    fn may_call(&self) -> bool {
+        self.may_use(&Operation::Call)
+    }
+
+    fn may_write_table(&self) -> bool {
+        match &self.operation_from {
+            Some(from) => from.values.values().any(|op| *op != Operation::Call),
+            None => self.operation != Operation::Call,
+        }
+    }
+
+    fn may_use(&self, operation: &Operation) -> bool {
+        match &self.operation_from {
+            Some(from) => from.values.values().any(|op| op == operation),
+            None => self.operation == *operation,
+        }
+    }
+
+    /// Every mapped column must be passed to the routine exactly once.
+    fn validate_call(&self) -> eyre::Result<()> {
+        let routine = self
+            .routine
+            .as_ref()
+            .ok_or_else(|| eyre!("routine can't be empty when doing call"))?;
+        let mut args = HashSet::with_capacity(routine.args.len());
+        for arg in routine.args.iter() {
+            if !args.insert(arg) {
+                return Err(eyre!("routine argument is listed twice: {}", arg));
+            }
+            if !self.columns.contains_key(arg) {
+                return Err(eyre!("routine argument is not mapped: {}", arg));
+            }
+        }
+        match self
+            .columns
+            .keys()
+            .filter(|name| !args.contains(name))
+            .min()
+        {
+            Some(name) => Err(eyre!("mapped column is not a routine argument: {}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Change data capture mode, the record is a change event wrapping the row images.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Cdc {
+    pub format: CdcFormat,
+    /// Columns identifying the row, used to upsert and delete it
+    pub primary_key: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CdcFormat {
+    /// Debezium envelope with `before`, `after` and `op` fields
+    Debezium,
+}
+
+/// Handling of records whose discriminator value has no route.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Fallback {
+    /// The record is rejected
+    #[default]
+    Error,
+    /// The record produces no operations
+    Skip,
+    /// The record is transformed with the default mapping
+    Mapping(Box<Mapping>),
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
@@ -23,6 +277,46 @@ pub struct Mapping {
 pub enum Operation {
     Insert,
     Upsert,
+    Call,
+    Delete,
+}
+
+/// Operation selected per record by the value of the JSON path.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct OperationFrom {
+    pub json_key: JsonPath,
+    /// Operations by the values, non-string values are matched by their JSON text
+    pub values: HashMap<String, Operation>,
+}
+
+/// Stored procedure or function called instead of writing to a table.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Routine {
+    pub name: String,
+    #[serde(default)]
+    pub kind: RoutineKind,
+    /// Columns passed as the routine arguments, in order
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RoutineKind {
+    #[default]
+    Function,
+    Procedure,
+}
+
+impl From<RoutineKind> for fluvio_model_sql::RoutineKind {
+    fn from(kind: RoutineKind) -> Self {
+        match kind {
+            RoutineKind::Function => Self::Function,
+            RoutineKind::Procedure => Self::Procedure,
+        }
+    }
 }
 
 fn default_op() -> Operation {
@@ -31,8 +325,11 @@ fn default_op() -> Operation {
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Column {
-    #[serde(alias = "json-key")]
-    pub json_key: String,
+    #[serde(default, alias = "json-key", skip_serializing_if = "Option::is_none")]
+    pub json_key: Option<JsonPath>,
+    /// Expression computing the value, used instead of `json_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expr: Option<Expression>,
     pub value: Value,
 }
 
@@ -44,9 +341,117 @@ pub struct Value {
     pub default: Option<String>,
     #[serde(default)]
     pub required: bool,
+    /// Input format of date and time values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<TimeFormat>,
+    /// SQL expression applied to the value placeholder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<Cast>,
+    /// Encoding of bytes values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+    /// Value generated when the field is missing and there is no default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generate: Option<Generator>,
+}
+
+/// Generated value of a missing field.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum Generator {
+    /// Current time when the operation is written by the sink
+    Now,
+    /// Random UUID generated by the sink
+    UuidV4,
+    /// Time-ordered UUID generated by the sink
+    UuidV7,
+    /// Timestamp of the Fluvio record
+    RecordTimestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Cast {
+    Type(String),
+    Function(String),
+}
+
+impl From<Cast> for fluvio_model_sql::Cast {
+    fn from(cast: Cast) -> Self {
+        match cast {
+            Cast::Type(name) => Self::Type(name),
+            Cast::Function(name) => Self::Function(name),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    Raw,
+    Base64,
+    Base64Url,
+    Hex,
+}
+
+impl From<Encoding> for fluvio_model_sql::Encoding {
+    fn from(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Raw => Self::Raw,
+            Encoding::Base64 => Self::Base64,
+            Encoding::Base64Url => Self::Base64Url,
+            Encoding::Hex => Self::Hex,
+        }
+    }
+}
+
+/// Format of date and time input values, normalized before building the SQL operation.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub enum TimeFormat {
+    /// RFC 3339 timestamp, e.g. `2024-01-01T10:00:00Z`
+    Rfc3339,
+    /// Seconds since Unix epoch
+    EpochSeconds,
+    /// Milliseconds since Unix epoch
+    EpochMillis,
+    /// Microseconds since Unix epoch
+    EpochMicros,
+    /// strftime-like pattern, e.g. `%d/%m/%Y %H:%M`
+    Pattern(String),
+}
+
+impl TryFrom<String> for TimeFormat {
+    type Error = String;
+
+    fn try_from(format: String) -> Result<Self, Self::Error> {
+        match format.as_str() {
+            "rfc3339" => Ok(Self::Rfc3339),
+            "epoch-s" => Ok(Self::EpochSeconds),
+            "epoch-ms" => Ok(Self::EpochMillis),
+            "epoch-us" => Ok(Self::EpochMicros),
+            // patterns without specifiers are most likely misspelled format names
+            _ if format.contains('%') => Ok(Self::Pattern(format)),
+            _ => Err(format!(
+                "unknown format `{format}`, expected rfc3339, epoch-s, epoch-ms, epoch-us or a pattern with % specifiers"
+            )),
+        }
+    }
+}
+
+impl From<TimeFormat> for String {
+    fn from(format: TimeFormat) -> Self {
+        match format {
+            TimeFormat::Rfc3339 => "rfc3339".to_string(),
+            TimeFormat::EpochSeconds => "epoch-s".to_string(),
+            TimeFormat::EpochMillis => "epoch-ms".to_string(),
+            TimeFormat::EpochMicros => "epoch-us".to_string(),
+            TimeFormat::Pattern(pattern) => pattern,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ValueType {
     #[serde(alias = "int8")]
@@ -56,6 +461,19 @@ pub enum ValueType {
     Integer,
     #[serde(alias = "int2")]
     Smallint,
+    #[serde(alias = "int1")]
+    Tinyint,
+    #[serde(
+        rename = "ubigint",
+        alias = "uint8",
+        alias = "u64",
+        alias = "bigint unsigned"
+    )]
+    UnsignedBigint,
+    #[serde(rename = "hugeint", alias = "int16", alias = "i128")]
+    Hugeint,
+    #[serde(rename = "uhugeint", alias = "uint16", alias = "u128")]
+    UnsignedHugeint,
 
     #[serde(alias = "bool")]
     Boolean,
@@ -76,10 +494,68 @@ pub enum ValueType {
 
     Date,
     Time,
+    Interval,
     Timestamp,
+    #[serde(alias = "timestamp with time zone")]
+    TimestampTz,
     #[serde(alias = "jsonb")]
     Json,
     Uuid,
+
+    Inet,
+    Cidr,
+    #[serde(alias = "macaddr")]
+    MacAddr,
+
+    #[serde(rename = "bigint[]", alias = "int8[]")]
+    BigintArray,
+    #[serde(rename = "integer[]", alias = "int4[]", alias = "int[]")]
+    IntegerArray,
+    #[serde(rename = "smallint[]", alias = "int2[]")]
+    SmallintArray,
+    #[serde(rename = "boolean[]", alias = "bool[]")]
+    BooleanArray,
+    #[serde(rename = "text[]", alias = "varchar[]")]
+    TextArray,
+    #[serde(rename = "float[]", alias = "float4[]", alias = "real[]")]
+    FloatArray,
+    #[serde(
+        rename = "doubleprecision[]",
+        alias = "double precision[]",
+        alias = "float8[]"
+    )]
+    DoublePrecisionArray,
+    #[serde(rename = "numeric[]", alias = "decimal[]")]
+    NumericArray,
+    #[serde(rename = "uuid[]")]
+    UuidArray,
+
+    /// Fixed-length character string, e.g. `{"char": 3}`, not padded for `{"char": null}`
+    Char(Option<u32>),
+
+    /// Postgres enum or domain type name, e.g. `{"enum": "order_status"}`
+    Enum(String),
+}
+
+impl ValueType {
+    /// Whether the current time can be written to the type: date and time types take their own
+    /// representation, `bigint`, `hugeint` (signed or unsigned) and `numeric` milliseconds since
+    /// Unix epoch and `text` RFC 3339.
+    fn holds_time(&self) -> bool {
+        matches!(
+            self,
+            ValueType::Timestamp
+                | ValueType::TimestampTz
+                | ValueType::Date
+                | ValueType::Time
+                | ValueType::Bigint
+                | ValueType::UnsignedBigint
+                | ValueType::Hugeint
+                | ValueType::UnsignedHugeint
+                | ValueType::Numeric
+                | ValueType::Text
+        )
+    }
 }
 
 impl From<ValueType> for Type {
@@ -88,6 +564,10 @@ impl From<ValueType> for Type {
             ValueType::Bigint => Type::BigInt,
             ValueType::Integer => Type::Int,
             ValueType::Smallint => Type::SmallInt,
+            ValueType::Tinyint => Type::TinyInt,
+            ValueType::UnsignedBigint => Type::UnsignedBigInt,
+            ValueType::Hugeint => Type::Int128,
+            ValueType::UnsignedHugeint => Type::UnsignedInt128,
             ValueType::Boolean => Type::Bool,
             ValueType::Bytea => Type::Bytes,
             ValueType::Text => Type::Text,
@@ -96,9 +576,25 @@ impl From<ValueType> for Type {
             ValueType::Numeric => Type::Numeric,
             ValueType::Date => Type::Date,
             ValueType::Time => Type::Time,
+            ValueType::Interval => Type::Interval,
             ValueType::Timestamp => Type::Timestamp,
+            ValueType::TimestampTz => Type::TimestampTz,
             ValueType::Json => Type::Json,
             ValueType::Uuid => Type::Uuid,
+            ValueType::Inet => Type::Inet,
+            ValueType::Cidr => Type::Cidr,
+            ValueType::MacAddr => Type::MacAddr,
+            ValueType::BigintArray => Type::Array(ElementType::BigInt),
+            ValueType::IntegerArray => Type::Array(ElementType::Int),
+            ValueType::SmallintArray => Type::Array(ElementType::SmallInt),
+            ValueType::BooleanArray => Type::Array(ElementType::Bool),
+            ValueType::TextArray => Type::Array(ElementType::Text),
+            ValueType::FloatArray => Type::Array(ElementType::Float),
+            ValueType::DoublePrecisionArray => Type::Array(ElementType::DoublePrecision),
+            ValueType::NumericArray => Type::Array(ElementType::Numeric),
+            ValueType::UuidArray => Type::Array(ElementType::Uuid),
+            ValueType::Char(length) => Type::Char(length),
+            ValueType::Enum(name) => Type::Enum(name),
         }
     }
 }
@@ -233,16 +729,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Insert,
+                operation_from: None,
                 unique_columns: Default::default(),
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: Some("4".to_string()),
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
@@ -280,16 +791,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Upsert,
+                operation_from: None,
                 unique_columns: vec!["my_idx".to_owned(), "my_idx2".to_owned()],
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: Some("4".to_string()),
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
@@ -297,6 +823,481 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deserialize_call() {
+        // given
+        let input = json!({
+            "operation": "call",
+            "routine": {
+                "name": "billing.apply_payment",
+                "kind": "procedure",
+                "args": ["customer", "amount"]
+            },
+            "map-columns": {
+                "customer" : {
+                    "json-key": "customer.id",
+                    "value": {
+                        "type": "int4"
+                    }
+                },
+                "amount" : {
+                    "json-key": "amount",
+                    "value": {
+                        "type": "numeric"
+                    }
+                }
+            }
+        });
+
+        // when
+        let mapping: Mapping = serde_json::from_value(input).expect("valid mapping");
+
+        // then
+        assert_eq!(mapping.operation, Operation::Call);
+        assert!(mapping.table.is_empty());
+        assert_eq!(
+            mapping.routine,
+            Some(Routine {
+                name: "billing.apply_payment".to_string(),
+                kind: RoutineKind::Procedure,
+                args: vec!["customer".to_string(), "amount".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_call() {
+        // given
+        let mapping = |args: serde_json::Value| -> Mapping {
+            serde_json::from_value(json!({
+                "operation": "call",
+                "routine": {
+                    "name": "apply_payment",
+                    "args": args
+                },
+                "map-columns": {
+                    "customer" : {
+                        "json-key": "customer",
+                        "value": {
+                            "type": "int4"
+                        }
+                    },
+                    "amount" : {
+                        "json-key": "amount",
+                        "value": {
+                            "type": "numeric"
+                        }
+                    }
+                }
+            }))
+            .expect("valid mapping")
+        };
+
+        // then
+        assert!(mapping(json!(["customer", "amount"])).validate().is_ok());
+        assert_eq!(
+            mapping(json!(["customer"]))
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "mapped column is not a routine argument: amount"
+        );
+        assert_eq!(
+            mapping(json!(["customer", "amount", "customer"]))
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "routine argument is listed twice: customer"
+        );
+        assert_eq!(
+            mapping(json!(["customer", "amount", "currency"]))
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "routine argument is not mapped: currency"
+        );
+    }
+
+    #[test]
+    fn test_validate_modes() {
+        // given
+        let validate = |mapping: serde_json::Value| {
+            serde_json::from_value::<Mapping>(mapping)
+                .expect("valid mapping")
+                .validate()
+                .map_err(|err| err.to_string())
+        };
+        let columns = json!({
+            "id": { "json-key": "id", "value": { "type": "int" } }
+        });
+        let table = json!({ "table": "t", "map-columns": columns });
+
+        // then
+        assert_eq!(validate(table.clone()), Ok(()));
+        assert_eq!(
+            validate(json!({ "tables": [table], "transaction": true })),
+            Ok(())
+        );
+        assert_eq!(
+            validate(json!({ "tables": [table], "map-columns": columns })),
+            Err("map-columns can't be used with tables".to_string())
+        );
+        assert_eq!(
+            validate(json!({
+                "table": "t",
+                "cdc": { "format": "debezium", "primary-key": ["id"] },
+                "explode": "$.items",
+                "map-columns": columns
+            })),
+            Err("cdc and explode can't be used together".to_string())
+        );
+        assert_eq!(
+            validate(json!({
+                "table": "t",
+                "cdc": { "format": "debezium", "primary-key": ["key"] },
+                "map-columns": columns
+            })),
+            Err("primary key column is not mapped: key".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "map-columns": columns })),
+            Err("table can't be empty".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "table": "t" })),
+            Err("map-columns can't be empty".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "table": "t", "operation": "upsert", "map-columns": columns })),
+            Err("unique-columns can't be empty when doing upsert".to_string())
+        );
+        assert_eq!(
+            validate(json!({
+                "table": "t",
+                "operation": "delete",
+                "unique-columns": ["key"],
+                "map-columns": columns
+            })),
+            Err("unique column is not mapped: key".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "tables": [{ "map-columns": columns }] })),
+            Err("table can't be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_generate() {
+        // given
+        let validate = |type_: &str, generate: &str| {
+            serde_json::from_value::<Mapping>(json!({
+                "table": "t",
+                "map-columns": {
+                    "at": { "value": { "type": type_, "generate": generate } }
+                }
+            }))
+            .expect("valid mapping")
+            .validate()
+            .map_err(|err| err.to_string())
+        };
+
+        // then
+        for type_ in ["timestamptz", "date", "bigint", "numeric", "text"] {
+            assert_eq!(validate(type_, "now"), Ok(()), "{type_}");
+            assert_eq!(validate(type_, "record-timestamp"), Ok(()), "{type_}");
+        }
+        assert_eq!(
+            validate("int", "now"),
+            Err("Now can't be generated for column at of type Integer".to_string())
+        );
+        assert_eq!(
+            validate("smallint", "record-timestamp"),
+            Err("RecordTimestamp can't be generated for column at of type Smallint".to_string())
+        );
+        assert!(validate("tinyint", "now").is_err());
+    }
+
+    #[test]
+    fn test_validate_routing() {
+        // given
+        let validate = |mapping: serde_json::Value| {
+            serde_json::from_value::<Mapping>(mapping)
+                .expect("valid mapping")
+                .validate()
+                .map_err(|err| err.to_string())
+        };
+        let table = json!({
+            "table": "t",
+            "map-columns": { "id": { "json-key": "id", "value": { "type": "int" } } }
+        });
+
+        // then
+        assert_eq!(
+            validate(json!({
+                "discriminator": "$.type",
+                "routes": { "created": table },
+                "fallback": { "mapping": table }
+            })),
+            Ok(())
+        );
+        assert_eq!(
+            validate(json!({ "discriminator": "$.type", "fallback": "skip" })),
+            Err("routes can't be empty when using discriminator".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "tables": [table], "routes": { "created": table } })),
+            Err("routes can't be used without discriminator".to_string())
+        );
+        assert_eq!(
+            validate(json!({ "tables": [table], "fallback": "skip" })),
+            Err("fallback can't be used without discriminator".to_string())
+        );
+        assert_eq!(
+            validate(json!({
+                "discriminator": "$.type",
+                "routes": { "created": { "table": "t" } }
+            })),
+            Err("map-columns can't be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_timestamptz() {
+        // given
+        let types = ["timestamptz", "timestamp with time zone"];
+
+        for type_ in types {
+            // when
+            let value: Value =
+                serde_json::from_value(json!({ "type": type_ })).expect("valid value");
+
+            // then
+            assert_eq!(value.type_, ValueType::TimestampTz);
+            assert_eq!(Type::from(value.type_), Type::TimestampTz);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_time_format() {
+        // given
+        let formats = [
+            ("rfc3339", TimeFormat::Rfc3339),
+            ("epoch-s", TimeFormat::EpochSeconds),
+            ("epoch-ms", TimeFormat::EpochMillis),
+            ("epoch-us", TimeFormat::EpochMicros),
+            ("%d/%m/%Y", TimeFormat::Pattern("%d/%m/%Y".to_string())),
+        ];
+
+        for (format, expected) in formats {
+            // when
+            let value: Value = serde_json::from_value(json!({
+                "type": "timestamp",
+                "format": format
+            }))
+            .expect("valid value");
+
+            // then
+            assert_eq!(value.format, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_deserialize_unknown_time_format() {
+        // when
+        let res = serde_json::from_value::<Value>(json!({
+            "type": "timestamp",
+            "format": "epoch-sec"
+        }));
+
+        // then
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .starts_with("unknown format `epoch-sec`"));
+    }
+
+    #[test]
+    fn test_deserialize_array_types() {
+        // given
+        let types = [
+            ("int[]", Type::Array(ElementType::Int)),
+            ("int4[]", Type::Array(ElementType::Int)),
+            ("bigint[]", Type::Array(ElementType::BigInt)),
+            ("text[]", Type::Array(ElementType::Text)),
+            ("uuid[]", Type::Array(ElementType::Uuid)),
+            (
+                "double precision[]",
+                Type::Array(ElementType::DoublePrecision),
+            ),
+        ];
+
+        for (type_, expected) in types {
+            // when
+            let value: Value =
+                serde_json::from_value(json!({ "type": type_ })).expect("valid value");
+
+            // then
+            assert_eq!(Type::from(value.type_), expected);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_network_types() {
+        // given
+        let types = [
+            ("interval", Type::Interval),
+            ("inet", Type::Inet),
+            ("cidr", Type::Cidr),
+            ("macaddr", Type::MacAddr),
+        ];
+
+        for (type_, expected) in types {
+            // when
+            let value: Value =
+                serde_json::from_value(json!({ "type": type_ })).expect("valid value");
+
+            // then
+            assert_eq!(Type::from(value.type_), expected);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_big_integer_types() {
+        // given
+        let types = [
+            ("ubigint", Type::UnsignedBigInt),
+            ("uint8", Type::UnsignedBigInt),
+            ("u64", Type::UnsignedBigInt),
+            ("bigint unsigned", Type::UnsignedBigInt),
+            ("hugeint", Type::Int128),
+            ("int16", Type::Int128),
+            ("i128", Type::Int128),
+            ("uhugeint", Type::UnsignedInt128),
+            ("u128", Type::UnsignedInt128),
+        ];
+
+        for (type_, expected) in types {
+            // when
+            let value: Value =
+                serde_json::from_value(json!({ "type": type_ })).expect("valid value");
+
+            // then
+            assert_eq!(Type::from(value.type_), expected);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_char_types() {
+        // given
+        let types = [
+            (json!({ "char": 3 }), Type::Char(Some(3))),
+            (json!({ "char": null }), Type::Char(None)),
+            (json!("tinyint"), Type::TinyInt),
+            (json!("int1"), Type::TinyInt),
+        ];
+
+        for (type_, expected) in types {
+            // when
+            let value: Value =
+                serde_json::from_value(json!({ "type": type_ })).expect("valid value");
+
+            // then
+            assert_eq!(Type::from(value.type_), expected);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_enum_type() {
+        // given
+        let input = json!({
+            "type": { "enum": "order_status" },
+            "required": true
+        });
+
+        // when
+        let value: Value = serde_json::from_value(input).expect("valid value");
+
+        // then
+        assert_eq!(value.type_, ValueType::Enum("order_status".to_string()));
+        assert_eq!(
+            Type::from(value.type_),
+            Type::Enum("order_status".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_cast() {
+        // given
+        let input = json!({
+            "type": "text",
+            "cast": { "function": "ST_GeomFromGeoJSON" }
+        });
+
+        // when
+        let value: Value = serde_json::from_value(input).expect("valid value");
+
+        // then
+        assert_eq!(
+            value.cast,
+            Some(Cast::Function("ST_GeomFromGeoJSON".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_deserialize_encoding() {
+        // given
+        let encodings = [
+            ("raw", Encoding::Raw),
+            ("base64", Encoding::Base64),
+            ("base64url", Encoding::Base64Url),
+            ("hex", Encoding::Hex),
+        ];
+
+        for (encoding, expected) in encodings {
+            // when
+            let value: Value = serde_json::from_value(json!({
+                "type": "bytea",
+                "encoding": encoding
+            }))
+            .expect("valid value");
+
+            // then
+            assert_eq!(value.encoding, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_deserialize_cdc() {
+        // given
+        let input = json!({
+            "table": "users",
+            "cdc": {
+                "format": "debezium",
+                "primary-key": ["id"]
+            },
+            "columns": {
+                "id": {
+                    "json-key": "@.id",
+                    "value": {
+                        "type": "int"
+                    }
+                }
+            }
+        });
+
+        // when
+        let mapping: Mapping = serde_json::from_value(input).expect("valid mapping");
+
+        // then
+        assert_eq!(
+            mapping.cdc,
+            Some(Cdc {
+                format: CdcFormat::Debezium,
+                primary_key: vec!["id".to_string()],
+            })
+        );
+    }
+
     #[test]
     fn test_default_value_as_int() {
         // given
@@ -321,16 +1322,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Insert,
+                operation_from: None,
                 unique_columns: Default::default(),
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: Some("4".to_string()),
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
@@ -362,16 +1378,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Insert,
+                operation_from: None,
                 unique_columns: Default::default(),
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: Some("4.5".to_string()),
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
@@ -403,16 +1434,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Insert,
+                operation_from: None,
                 unique_columns: Default::default(),
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: Some("-5".to_string()),
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
@@ -442,16 +1488,31 @@ mod tests {
             mapping,
             Mapping {
                 operation: Operation::Insert,
+                operation_from: None,
                 unique_columns: Default::default(),
+                routine: None,
+                explode: None,
+                tables: vec![],
+                transaction: false,
+                discriminator: None,
+                routes: HashMap::new(),
+                fallback: Fallback::Error,
+                cdc: None,
+                filter: None,
                 table: "test_table".to_string(),
                 columns: HashMap::from([(
                     "column_name".to_string(),
                     Column {
-                        json_key: "test-key".to_string(),
+                        json_key: Some(JsonPath::parse("test-key").unwrap()),
+                        expr: None,
                         value: Value {
                             type_: ValueType::Integer,
                             default: None,
-                            required: false
+                            required: false,
+                            format: None,
+                            cast: None,
+                            encoding: None,
+                            generate: None
                         }
                     }
                 )])
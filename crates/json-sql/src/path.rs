@@ -0,0 +1,1060 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+use eyre::eyre;
+use fluvio_smartmodule::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compare;
+
+/// JSONPath expression selecting the column value from the record.
+///
+/// Besides the JSONPath syntax (`$['a.b']`, `$.items[0]`, `$..id`, `$.items[*]`,
+/// `$.items[?(@.qty > 1)]`), the dotted paths without the root (`device.id`, `.device.id`)
+/// and JSON Pointers (`/device/id`) are accepted, their numeric segments select array elements.
+/// Paths starting with `@` select from the current element of the exploded array.
+///
+/// The pseudo-paths `@key`, `@offset`, `@partition` and `@timestamp` select the metadata of the
+/// Fluvio record.
+/// The key is selected as text, or parsed as JSON when followed by segments, e.g. `@key.id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JsonPath {
+    source: String,
+    root: Root,
+    segments: Vec<Segment>,
+}
+
+/// Node the path selects from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Root {
+    Record,
+    Element,
+    Key,
+    Offset,
+    Partition,
+    Timestamp,
+}
+
+/// Metadata of the Fluvio record the value is read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordMetadata {
+    pub key: Option<String>,
+    pub offset: Option<i64>,
+    /// Not part of the record, configured with the `partition` parameter
+    pub partition: Option<i32>,
+    /// Milliseconds since Unix epoch
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    /// Object member, or array element if the name is an index, from the dotted notation
+    Member(String),
+    /// Object member from the bracket notation
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Expr),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Exists(Query),
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+}
+
+#[derive(Debug, Clone)]
+struct Query {
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser::new(source);
+        let (root, segments) = parser
+            .root()
+            .and_then(|root| Ok((root, parser.path(root)?)))
+            .map_err(|err| eyre!("invalid json path `{source}`: {err}"))?;
+        Ok(Self {
+            source: source.to_string(),
+            root,
+            segments,
+        })
+    }
+
+    /// Selects the value from the record.
+    ///
+    /// A path selecting at most one node (only member names and indexes) yields the node itself.
+    /// Other paths yield the array of all matched nodes in document order, or nothing if none matched.
+    pub fn select<'a>(
+        &self,
+        record: &'a Value,
+        metadata: &RecordMetadata,
+    ) -> Option<Cow<'a, Value>> {
+        self.select_in(record, record, metadata)
+    }
+
+    /// Selects the value from the record, or from the current element for relative paths.
+    pub fn select_in<'a>(
+        &self,
+        record: &'a Value,
+        current: &'a Value,
+        metadata: &RecordMetadata,
+    ) -> Option<Cow<'a, Value>> {
+        let start = match self.root {
+            Root::Record => record,
+            Root::Element => current,
+            Root::Key if self.segments.is_empty() => {
+                return metadata
+                    .key
+                    .clone()
+                    .map(|key| Cow::Owned(Value::String(key)));
+            }
+            Root::Key => {
+                let key: Value = serde_json::from_str(metadata.key.as_deref()?).ok()?;
+                let nodes = select(&self.segments, &key, &key);
+                return self
+                    .matched(nodes)
+                    .map(|node| Cow::Owned(node.into_owned()));
+            }
+            Root::Offset => return metadata.offset.map(|offset| Cow::Owned(offset.into())),
+            Root::Partition => {
+                return metadata
+                    .partition
+                    .map(|partition| Cow::Owned(partition.into()))
+            }
+            Root::Timestamp => {
+                return metadata
+                    .timestamp
+                    .map(|timestamp| Cow::Owned(timestamp.into()))
+            }
+        };
+        self.matched(select(&self.segments, record, start))
+    }
+
+    fn matched<'a>(&self, nodes: Vec<&'a Value>) -> Option<Cow<'a, Value>> {
+        if self.is_singular() {
+            nodes.into_iter().next().map(Cow::Borrowed)
+        } else if nodes.is_empty() {
+            None
+        } else {
+            Some(Cow::Owned(Value::Array(
+                nodes.into_iter().cloned().collect(),
+            )))
+        }
+    }
+
+    fn is_singular(&self) -> bool {
+        is_singular(&self.segments)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for JsonPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for JsonPath {}
+
+impl TryFrom<String> for JsonPath {
+    type Error = eyre::Report;
+
+    fn try_from(source: String) -> Result<Self> {
+        Self::parse(&source)
+    }
+}
+
+impl From<JsonPath> for String {
+    fn from(path: JsonPath) -> Self {
+        path.source
+    }
+}
+
+fn is_singular(segments: &[Segment]) -> bool {
+    segments.iter().all(|segment| match segment {
+        Segment::Child(selectors) => matches!(
+            selectors.as_slice(),
+            [Selector::Member(_) | Selector::Name(_) | Selector::Index(_)]
+        ),
+        Segment::Descendant(_) => false,
+    })
+}
+
+fn select<'a>(segments: &[Segment], root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+    let mut nodes = vec![current];
+    for segment in segments {
+        let mut selected = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(selectors) => {
+                    for selector in selectors {
+                        select_children(selector, root, node, &mut selected);
+                    }
+                }
+                Segment::Descendant(selectors) => {
+                    let mut descendants = Vec::new();
+                    collect_descendants(node, &mut descendants);
+                    for descendant in descendants {
+                        for selector in selectors {
+                            select_children(selector, root, descendant, &mut selected);
+                        }
+                    }
+                }
+            }
+        }
+        nodes = selected;
+    }
+    nodes
+}
+
+/// Collects the node and all its descendants in document order.
+fn collect_descendants<'a>(node: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(node);
+    match node {
+        Value::Array(items) => items.iter().for_each(|item| collect_descendants(item, out)),
+        Value::Object(members) => members
+            .values()
+            .for_each(|member| collect_descendants(member, out)),
+        _ => {}
+    }
+}
+
+fn select_children<'a>(
+    selector: &Selector,
+    root: &'a Value,
+    node: &'a Value,
+    out: &mut Vec<&'a Value>,
+) {
+    match (selector, node) {
+        (Selector::Member(name), Value::Object(members))
+        | (Selector::Name(name), Value::Object(members)) => out.extend(members.get(name)),
+        (Selector::Member(name), Value::Array(items)) => {
+            if let Ok(index) = name.parse::<usize>() {
+                out.extend(items.get(index));
+            }
+        }
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items.iter()),
+        (Selector::Wildcard, Value::Object(members)) => out.extend(members.values()),
+        (Selector::Index(index), Value::Array(items)) => {
+            let len = items.len() as i64;
+            let index = if *index < 0 { len + index } else { *index };
+            if (0..len).contains(&index) {
+                out.push(&items[index as usize]);
+            }
+        }
+        (Selector::Slice { start, end, step }, Value::Array(items)) => {
+            slice(items, *start, *end, *step, out)
+        }
+        (Selector::Filter(expr), Value::Array(items)) => {
+            out.extend(items.iter().filter(|item| expr.test(root, item)))
+        }
+        (Selector::Filter(expr), Value::Object(members)) => {
+            out.extend(members.values().filter(|member| expr.test(root, member)))
+        }
+        _ => {}
+    }
+}
+
+fn slice<'a>(
+    items: &'a [Value],
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+    out: &mut Vec<&'a Value>,
+) {
+    let len = items.len() as i64;
+    let step = step.unwrap_or(1);
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            out.push(&items[i as usize]);
+            let Some(next) = i.checked_add(step) else {
+                break;
+            };
+            i = next;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = end.map(normalize).unwrap_or(-len - 1).clamp(-1, len - 1);
+        let mut i = upper;
+        while i > lower {
+            out.push(&items[i as usize]);
+            let Some(next) = i.checked_add(step) else {
+                break;
+            };
+            i = next;
+        }
+    }
+}
+
+impl Expr {
+    fn test(&self, root: &Value, current: &Value) -> bool {
+        match self {
+            Expr::Or(left, right) => left.test(root, current) || right.test(root, current),
+            Expr::And(left, right) => left.test(root, current) && right.test(root, current),
+            Expr::Not(expr) => !expr.test(root, current),
+            Expr::Exists(query) => !query.select(root, current).is_empty(),
+            Expr::Compare(left, op, right) => {
+                let left = left.evaluate(root, current);
+                let right = right.evaluate(root, current);
+                compare(left.as_deref(), *op, right.as_deref())
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn evaluate<'a>(&'a self, root: &'a Value, current: &'a Value) -> Option<Cow<'a, Value>> {
+        match self {
+            Operand::Literal(value) => Some(Cow::Borrowed(value)),
+            Operand::Query(query) => query
+                .select(root, current)
+                .into_iter()
+                .next()
+                .map(Cow::Borrowed),
+        }
+    }
+}
+
+impl Query {
+    fn select<'a>(&self, root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+        let start = if self.absolute { root } else { current };
+        select(&self.segments, root, start)
+    }
+}
+
+/// Compares the values, a missing value is only equal to another missing value.
+fn compare(left: Option<&Value>, op: CompareOp, right: Option<&Value>) -> bool {
+    match op {
+        CompareOp::Eq => equals(left, right),
+        CompareOp::Ne => !equals(left, right),
+        CompareOp::Lt => order(left, right) == Some(Ordering::Less),
+        CompareOp::Le => order(left, right).is_some_and(Ordering::is_le) || equals(left, right),
+        CompareOp::Gt => order(left, right) == Some(Ordering::Greater),
+        CompareOp::Ge => order(left, right).is_some_and(Ordering::is_ge) || equals(left, right),
+    }
+}
+
+fn equals(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (None, None) => true,
+        (Some(left), Some(right)) => compare::equals(left, right),
+        _ => false,
+    }
+}
+
+fn order(left: Option<&Value>, right: Option<&Value>) -> Option<Ordering> {
+    compare::order(left?, right?)
+}
+
+struct Parser<'s> {
+    chars: Vec<char>,
+    pos: usize,
+    source: &'s str,
+}
+
+type ParseResult<T> = std::result::Result<T, String>;
+
+impl<'s> Parser<'s> {
+    fn new(source: &'s str) -> Self {
+        Self {
+            chars: source.trim().chars().collect(),
+            pos: 0,
+            source,
+        }
+    }
+
+    fn root(&mut self) -> ParseResult<Root> {
+        if !self.eat('@') {
+            return Ok(Root::Record);
+        }
+        if !self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            return Ok(Root::Element);
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        let root = match name.as_str() {
+            "key" => Root::Key,
+            "offset" => Root::Offset,
+            "partition" => Root::Partition,
+            "timestamp" => Root::Timestamp,
+            _ => return Err(format!("unknown record metadata `@{name}`")),
+        };
+        if root != Root::Key && self.peek().is_some() {
+            return Err(format!("`@{name}` has no members"));
+        }
+        Ok(root)
+    }
+
+    fn path(&mut self, root: Root) -> ParseResult<Vec<Segment>> {
+        if root == Root::Record && self.peek() == Some('/') {
+            return Ok(self.pointer());
+        }
+        let mut segments = Vec::new();
+        if root == Root::Record
+            && !self.eat('$')
+            && self.peek().is_some_and(|c| c != '.' && c != '[')
+        {
+            // dotted path without the root, e.g. `device.id`
+            segments.push(Segment::Child(vec![Selector::Member(
+                self.dotted_name(false)?,
+            )]));
+        }
+        segments.extend(self.segments(false)?);
+        if let Some(c) = self.peek() {
+            return Err(format!("unexpected `{c}` at {}", self.pos));
+        }
+        Ok(segments)
+    }
+
+    /// JSON Pointer (RFC 6901), `~1` and `~0` are unescaped to `/` and `~`.
+    fn pointer(&mut self) -> Vec<Segment> {
+        let pointer: String = self.chars[self.pos..].iter().collect();
+        self.pos = self.chars.len();
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|token| {
+                let name = token.replace("~1", "/").replace("~0", "~");
+                Segment::Child(vec![Selector::Member(name)])
+            })
+            .collect()
+    }
+
+    fn segments(&mut self, in_filter: bool) -> ParseResult<Vec<Segment>> {
+        let mut segments = Vec::new();
+        loop {
+            if self.eat_str("..") {
+                let selectors = match self.peek() {
+                    Some('[') => self.bracket()?,
+                    Some('*') => {
+                        self.pos += 1;
+                        vec![Selector::Wildcard]
+                    }
+                    _ => vec![Selector::Member(self.dotted_name(in_filter)?)],
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.eat('.') {
+                let selector = if self.eat('*') {
+                    Selector::Wildcard
+                } else {
+                    Selector::Member(self.dotted_name(in_filter)?)
+                };
+                segments.push(Segment::Child(vec![selector]));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn dotted_name(&mut self, in_filter: bool) -> ParseResult<String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            let stop = if in_filter {
+                !(c.is_alphanumeric() || c == '_' || c == '-')
+            } else {
+                matches!(c, '.' | '[' | ']')
+            };
+            if stop {
+                break;
+            }
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(format!("expected member name at {start}"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn bracket(&mut self) -> ParseResult<Vec<Selector>> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> ParseResult<Selector> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                Ok(Selector::Filter(self.or()?))
+            }
+            _ => {
+                let start = self.optional_int()?;
+                self.skip_whitespace();
+                if !self.eat(':') {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| format!("expected selector at {}", self.pos));
+                }
+                self.skip_whitespace();
+                let end = self.optional_int()?;
+                self.skip_whitespace();
+                let step = if self.eat(':') {
+                    self.skip_whitespace();
+                    self.optional_int()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice { start, end, step })
+            }
+        }
+    }
+
+    fn or(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.and()?;
+        while self.eat_token("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.unary()?;
+        while self.eat_token("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> ParseResult<Expr> {
+        self.skip_whitespace();
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat('(') {
+            let expr = self.or()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(expr);
+        }
+        let left = self.operand()?;
+        let Some(op) = self.compare_op() else {
+            return match left {
+                Operand::Query(query) => Ok(Expr::Exists(query)),
+                Operand::Literal(_) => Err(format!("expected comparison at {}", self.pos)),
+            };
+        };
+        let right = self.operand()?;
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn compare_op(&mut self) -> Option<CompareOp> {
+        let ops = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        ops.into_iter()
+            .find(|(token, _)| self.eat_token(token))
+            .map(|(_, op)| op)
+    }
+
+    fn operand(&mut self) -> ParseResult<Operand> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c @ ('@' | '$')) => {
+                self.pos += 1;
+                Ok(Operand::Query(Query {
+                    absolute: c == '$',
+                    segments: self.segments(true)?,
+                }))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Value::String(self.string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => Ok(Operand::Literal(self.number()?)),
+            _ => {
+                for (token, value) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if self.eat_str(token) {
+                        return Ok(Operand::Literal(value));
+                    }
+                }
+                Err(format!("expected operand at {}", self.pos))
+            }
+        }
+    }
+
+    fn number(&mut self) -> ParseResult<Value> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        serde_json::from_str::<serde_json::Number>(&text)
+            .map(Value::Number)
+            .map_err(|_| format!("invalid number `{text}` at {start}"))
+    }
+
+    fn optional_int(&mut self) -> ParseResult<Option<i64>> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .map(Some)
+            .map_err(|_| format!("invalid index `{text}` at {start}"))
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let quote = self.next().ok_or("expected string")?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                None => return Err(format!("unterminated string in `{}`", self.source)),
+                Some(c) if c == quote => return Ok(text),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid unicode escape `{hex}`"))?
+                        }
+                        Some(c) => c,
+                        None => return Err("unterminated escape".to_string()),
+                    };
+                    text.push(escaped);
+                }
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        if self.chars.len() >= self.pos + len
+            && s.chars()
+                .eq(self.chars[self.pos..self.pos + len].iter().copied())
+        {
+            self.pos += len;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Eats the token surrounded by optional whitespace.
+    fn eat_token(&mut self, token: &str) -> bool {
+        let pos = self.pos;
+        self.skip_whitespace();
+        if self.eat_str(token) {
+            self.skip_whitespace();
+            true
+        } else {
+            self.pos = pos;
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(format!("expected `{c}` at {}", self.pos))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select_path(input: &Value, path: &str) -> Option<Value> {
+        JsonPath::parse(path)
+            .expect("valid path")
+            .select(input, &RecordMetadata::default())
+            .map(Cow::into_owned)
+    }
+
+    #[test]
+    fn test_root() {
+        //given
+        let input = json!({
+           "key1": "value1"
+        });
+
+        //when
+        let option1 = select_path(&input, "$");
+        let option2 = select_path(&input, "");
+
+        //then
+        assert_eq!(option1, Some(input.clone()));
+        assert_eq!(option2, Some(input));
+    }
+
+    #[test]
+    fn test_field() {
+        //given
+        let input = json!({
+           "key1": "value1"
+        });
+
+        //when
+        let option1 = select_path(&input, "key1");
+        let option2 = select_path(&input, ".key1");
+        let option3 = select_path(&input, "$.key1");
+        let option4 = select_path(&input, "$['key1']");
+
+        //then
+        let value = json!("value1");
+        assert_eq!(option1, Some(value.clone()));
+        assert_eq!(option2, Some(value.clone()));
+        assert_eq!(option3, Some(value.clone()));
+        assert_eq!(option4, Some(value));
+    }
+
+    #[test]
+    fn test_nested_field() {
+        //given
+        let input = json!({
+           "key1": {"key2": "value2"}
+        });
+
+        //when
+        let option1 = select_path(&input, "key1.key2");
+        let option2 = select_path(&input, ".key1.key2");
+        let option3 = select_path(&input, "$.key1.key2");
+        let option4 = select_path(&input, "$.key1.missing");
+
+        //then
+        let value = json!("value2");
+        assert_eq!(option1, Some(value.clone()));
+        assert_eq!(option2, Some(value.clone()));
+        assert_eq!(option3, Some(value));
+        assert_eq!(option4, None);
+    }
+
+    #[test]
+    fn test_nested_array() {
+        //given
+        let input = json!({
+           "key1": {"key2": ["one", "two", "three"]}
+        });
+
+        //when
+        let option1 = select_path(&input, "key1.key2.0");
+        let option2 = select_path(&input, ".key1.key2.1");
+        let option3 = select_path(&input, "$.key1.key2[2]");
+        let option4 = select_path(&input, "$.key1.key2[-1]");
+        let option5 = select_path(&input, "$.key1.key2[3]");
+
+        //then
+        assert_eq!(option1, Some(json!("one")));
+        assert_eq!(option2, Some(json!("two")));
+        assert_eq!(option3, Some(json!("three")));
+        assert_eq!(option4, Some(json!("three")));
+        assert_eq!(option5, None);
+    }
+
+    #[test]
+    fn test_special_keys() {
+        //given
+        let input = json!({
+           "a.b": 1,
+           "c/d": {"e f": 2},
+           "it's": 3
+        });
+
+        //when
+        let dotted = select_path(&input, "$['a.b']");
+        let slashed = select_path(&input, "$[\"c/d\"]['e f']");
+        let quoted = select_path(&input, "$['it\\'s']");
+
+        //then
+        assert_eq!(dotted, Some(json!(1)));
+        assert_eq!(slashed, Some(json!(2)));
+        assert_eq!(quoted, Some(json!(3)));
+    }
+
+    #[test]
+    fn test_multiple_matches_as_array() {
+        //given
+        let input = json!({
+           "items": [{"id": 1, "qty": 5}, {"id": 2, "qty": 1}, {"id": 3}],
+           "owner": {"id": 9}
+        });
+
+        //when
+        let wildcard = select_path(&input, "$.items[*].id");
+        let descendant = select_path(&input, "$..id");
+        let slice = select_path(&input, "$.items[0:2].id");
+        let reversed = select_path(&input, "$.items[::-1].id");
+        let union = select_path(&input, "$.items[0,2].id");
+        let none = select_path(&input, "$.items[*].missing");
+
+        //then
+        assert_eq!(wildcard, Some(json!([1, 2, 3])));
+        assert_eq!(descendant, Some(json!([1, 2, 3, 9])));
+        assert_eq!(slice, Some(json!([1, 2])));
+        assert_eq!(reversed, Some(json!([3, 2, 1])));
+        assert_eq!(union, Some(json!([1, 3])));
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn test_slice_step_overflow() {
+        //given
+        let input = json!([0, 1, 2, 3, 4, 5]);
+
+        //when
+        let forward = select_path(&input, "$[1:5:9223372036854775807]");
+        let backward = select_path(&input, "$[4:0:-9223372036854775808]");
+
+        //then
+        assert_eq!(forward, Some(json!([1])));
+        assert_eq!(backward, Some(json!([4])));
+    }
+
+    #[test]
+    fn test_json_pointer() {
+        //given
+        let input = json!({
+           "device": {"id": 1, "tags": ["a", "b"]},
+           "c/d": {"e~f": 2}
+        });
+
+        //when
+        let member = select_path(&input, "/device/id");
+        let element = select_path(&input, "/device/tags/1");
+        let escaped = select_path(&input, "/c~1d/e~0f");
+        let missing = select_path(&input, "/device/missing");
+
+        //then
+        assert_eq!(member, Some(json!(1)));
+        assert_eq!(element, Some(json!("b")));
+        assert_eq!(escaped, Some(json!(2)));
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_filter() {
+        //given
+        let input = json!({
+           "limit": 2,
+           "items": [
+               {"id": 1, "qty": 5, "tag": "a"},
+               {"id": 2, "qty": 1, "tag": "b"},
+               {"id": 3, "tag": "a"}
+           ]
+        });
+
+        //when
+        let compare = select_path(&input, "$.items[?(@.qty > 1)].id");
+        let exists = select_path(&input, "$.items[?@.qty].id");
+        let not_exists = select_path(&input, "$.items[?(!@.qty)].id");
+        let logical = select_path(
+            &input,
+            "$.items[?(@.tag == 'a' && @.qty >= 5 || @.id == 2)].id",
+        );
+        let absolute = select_path(&input, "$.items[?(@.id < $.limit)].id");
+
+        //then
+        assert_eq!(compare, Some(json!([1])));
+        assert_eq!(exists, Some(json!([1, 2])));
+        assert_eq!(not_exists, Some(json!([3])));
+        assert_eq!(logical, Some(json!([1, 2])));
+        assert_eq!(absolute, Some(json!([1])));
+    }
+
+    #[test]
+    fn test_relative_path() {
+        //given
+        let input = json!({
+           "order": 7,
+           "items": [{"sku": "a"}, {"sku": "b"}]
+        });
+        let element = &input["items"][1];
+
+        //when
+        let relative = JsonPath::parse("@.sku").unwrap();
+        let absolute = JsonPath::parse("$.order").unwrap();
+
+        //then
+        let metadata = RecordMetadata::default();
+        assert_eq!(
+            relative.select_in(&input, element, &metadata).as_deref(),
+            Some(&json!("b"))
+        );
+        assert_eq!(
+            absolute.select_in(&input, element, &metadata).as_deref(),
+            Some(&json!(7))
+        );
+        assert_eq!(relative.select(&input, &metadata), None);
+    }
+
+    #[test]
+    fn test_record_metadata() {
+        //given
+        let input = json!({"key": "value"});
+        let metadata = RecordMetadata {
+            key: Some(r#"{"id": 7, "tags": ["a"]}"#.to_string()),
+            offset: Some(42),
+            partition: Some(3),
+            timestamp: Some(1700000000000),
+        };
+        let select = |path: &str| {
+            JsonPath::parse(path)
+                .expect("valid path")
+                .select(&input, &metadata)
+                .map(Cow::into_owned)
+        };
+
+        //when
+        let key = select("@key");
+        let key_member = select("@key.id");
+        let key_wildcard = select("@key.tags[*]");
+        let offset = select("@offset");
+        let partition = select("@partition");
+        let timestamp = select("@timestamp");
+        let text_key_member = JsonPath::parse("@key.id").unwrap().select(
+            &input,
+            &RecordMetadata {
+                key: Some("plain".to_string()),
+                ..Default::default()
+            },
+        );
+
+        //then
+        assert_eq!(key, Some(json!(r#"{"id": 7, "tags": ["a"]}"#)));
+        assert_eq!(key_member, Some(json!(7)));
+        assert_eq!(key_wildcard, Some(json!(["a"])));
+        assert_eq!(offset, Some(json!(42)));
+        assert_eq!(partition, Some(json!(3)));
+        assert_eq!(timestamp, Some(json!(1700000000000i64)));
+        assert_eq!(text_key_member, None);
+        assert_eq!(
+            select("$.key"),
+            Some(json!("value")),
+            "record member named key"
+        );
+    }
+
+    #[test]
+    fn test_invalid_record_metadata() {
+        assert!(JsonPath::parse("@partition.id").is_err());
+        assert!(JsonPath::parse("@offset.value").is_err());
+        assert!(JsonPath::parse("@keys").is_err());
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        assert!(JsonPath::parse("$[").is_err());
+        assert!(JsonPath::parse("$.items[?(@.qty >)]").is_err());
+        assert!(JsonPath::parse("$['unterminated]").is_err());
+        assert!(JsonPath::parse("$.a]").is_err());
+    }
+}
@@ -1,36 +1,242 @@
-use crate::mapping::{Mapping, Operation as MappingOperation};
-use crate::pointer::pointer;
-use eyre::eyre;
-use fluvio_model_sql::{Insert, Operation, Type, Upsert, Value};
+use crate::format::normalize;
+use crate::mapping::{
+    Cdc, CdcFormat, Column, Fallback, Generator, Mapping, Operation as MappingOperation,
+    TimeFormat, ValueType,
+};
+use crate::path::{JsonPath, RecordMetadata};
+use eyre::{eyre, WrapErr};
+use fluvio_model_sql::{Call, Delete, Generated, Insert, Operation, Truncate, Type, Upsert, Value};
 use fluvio_smartmodule::Result;
+use std::borrow::Cow;
+use std::fmt;
 
-pub(crate) fn transform(record: serde_json::Value, mapping: &Mapping) -> Result<Operation> {
-    let mut values = Vec::with_capacity(mapping.columns.len());
-    for (name, column) in mapping.columns.iter() {
-        let raw_value = match pointer(&record, column.json_key.as_str()) {
-            None => match &column.value.default {
-                None => {
-                    if column.value.required {
-                        return Err(eyre!("Missing required field: {}", column.json_key));
-                    }
-                    String::new()
+/// Transforms the raw record value, the empty tombstones following deletes in CDC mode produce
+/// no operations.
+pub(crate) fn transform_value(
+    value: &[u8],
+    metadata: &RecordMetadata,
+    mapping: &Mapping,
+) -> Result<Vec<Operation>> {
+    if value.is_empty() && mapping.uses_cdc() {
+        return Ok(Vec::new());
+    }
+    let record = serde_json::from_slice(value)?;
+    transform_all(&record, metadata, mapping)
+}
+
+/// Transforms the record into operations: none if it doesn't match the filter, with the mapping
+/// routed by the discriminator, one per table mapping, from the change event in CDC mode,
+/// one per element of the exploded array if it is set, grouped into a transaction if requested.
+pub(crate) fn transform_all(
+    record: &serde_json::Value,
+    metadata: &RecordMetadata,
+    mapping: &Mapping,
+) -> Result<Vec<Operation>> {
+    if let Some(filter) = &mapping.filter {
+        if !filter.matches(record, metadata)? {
+            return Ok(Vec::new());
+        }
+    }
+
+    let operations = if let Some(discriminator) = &mapping.discriminator {
+        match route(record, metadata, discriminator, mapping)? {
+            Some(routed) => transform_all(record, metadata, routed)?,
+            None => Vec::new(),
+        }
+    } else if !mapping.tables.is_empty() {
+        let mut operations = Vec::new();
+        for table in mapping.tables.iter() {
+            operations.extend(transform_all(record, metadata, table)?);
+        }
+        operations
+    } else if let Some(cdc) = &mapping.cdc {
+        transform_change(record, metadata, cdc, mapping)?
+            .into_iter()
+            .collect()
+    } else if let Some(explode) = &mapping.explode {
+        match explode.select(record, metadata).as_deref() {
+            None => Vec::new(),
+            Some(serde_json::Value::Array(elements)) => elements
+                .iter()
+                .map(|element| transform_element(record, element, metadata, mapping))
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(eyre!("exploded value is not an array: {}", explode)),
+        }
+    } else {
+        vec![transform_element(record, record, metadata, mapping)?]
+    };
+
+    if mapping.transaction && !operations.is_empty() {
+        Ok(vec![Operation::Transaction(operations)])
+    } else {
+        Ok(operations)
+    }
+}
+
+/// Finds the mapping of the discriminator value, `None` if the record is skipped.
+fn route<'a>(
+    record: &serde_json::Value,
+    metadata: &RecordMetadata,
+    discriminator: &JsonPath,
+    mapping: &'a Mapping,
+) -> Result<Option<&'a Mapping>> {
+    let value = select_text(discriminator, record, record, metadata);
+    if let Some(routed) = value.as_ref().and_then(|value| mapping.routes.get(value)) {
+        return Ok(Some(routed));
+    }
+    match &mapping.fallback {
+        Fallback::Error => Err(eyre!(
+            "no mapping for discriminator {} value: {}",
+            discriminator,
+            value.as_deref().unwrap_or("null")
+        )),
+        Fallback::Skip => Ok(None),
+        Fallback::Mapping(fallback) => Ok(Some(fallback)),
+    }
+}
+
+/// Transforms the change event into the operation applying it, `None` if the event
+/// does not change rows.
+fn transform_change(
+    record: &serde_json::Value,
+    metadata: &RecordMetadata,
+    cdc: &Cdc,
+    mapping: &Mapping,
+) -> Result<Option<Operation>> {
+    match cdc.format {
+        CdcFormat::Debezium => transform_debezium(record, metadata, cdc, mapping),
+    }
+}
+
+/// Upserts the `after` image of create, update and read events, deletes the row of the
+/// `before` image of delete events and truncates the table on truncate events.
+/// Tombstones and logical decoding messages are skipped.
+fn transform_debezium(
+    record: &serde_json::Value,
+    metadata: &RecordMetadata,
+    cdc: &Cdc,
+    mapping: &Mapping,
+) -> Result<Option<Operation>> {
+    if cdc.primary_key.is_empty() {
+        return Err(eyre!("primary-key can't be empty in cdc mode"));
+    }
+    if mapping.table.is_empty() {
+        return Err(eyre!("table can't be empty"));
+    }
+
+    // the envelope is wrapped into `payload` when the schema is included
+    let envelope = match record.get("payload") {
+        Some(payload) if record.get("schema").is_some() => payload,
+        _ => record,
+    };
+    if envelope.is_null() {
+        return Ok(None);
+    }
+
+    let op = envelope
+        .get("op")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| eyre!("change event has no op field"))?;
+    let image = |name: &str| match envelope.get(name) {
+        Some(image) if image.is_object() => Ok(image),
+        _ => Err(eyre!("change event with op {} has no {} image", op, name)),
+    };
+
+    let operation = match op {
+        "c" | "u" | "r" => {
+            let values = map_columns(envelope, image("after")?, metadata, mapping)?;
+            if values.is_empty() {
+                return Err(eyre!("map-columns can't be empty"));
+            }
+            Operation::Upsert(Upsert {
+                table: mapping.table.clone(),
+                values,
+                uniq_idx: cdc.primary_key.join(","),
+            })
+        }
+        "d" => {
+            let before = image("before")?;
+            let mut key = Vec::with_capacity(cdc.primary_key.len());
+            for name in cdc.primary_key.iter() {
+                let column = mapping
+                    .columns
+                    .get(name)
+                    .ok_or_else(|| eyre!("primary key column is not mapped: {}", name))?;
+                let value = map_column(envelope, before, metadata, name, column)?;
+                if is_missing(&value) {
+                    return Err(eyre!(
+                        "primary key column {} is missing in the before image",
+                        name
+                    ));
                 }
-                Some(default) => default.clone(),
-            },
-            Some(serde_json::Value::String(text)) => text.clone(),
-            Some(found) => serde_json::to_string(found)?,
-        };
-        values.push(Value {
-            column: name.clone(),
-            raw_value,
-            type_: Type::from(column.value.type_),
-        });
+                key.push(value);
+            }
+            Operation::Delete(Delete {
+                table: mapping.table.clone(),
+                values: key,
+            })
+        }
+        "t" => Operation::Truncate(Truncate {
+            table: mapping.table.clone(),
+        }),
+        "m" => return Ok(None),
+        other => return Err(eyre!("unsupported change event op: {}", other)),
+    };
+
+    Ok(Some(operation))
+}
+
+/// Selects the text of the value matched against a table: strings as is, other values
+/// as their JSON text, `None` if the value is null or missing.
+fn select_text(
+    path: &JsonPath,
+    record: &serde_json::Value,
+    element: &serde_json::Value,
+    metadata: &RecordMetadata,
+) -> Option<String> {
+    match path.select_in(record, element, metadata).as_deref() {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(text)) => Some(text.clone()),
+        Some(other) => Some(other.to_string()),
+    }
+}
+
+/// Transforms the element of the record, relative paths of the columns select from the element.
+fn transform_element(
+    record: &serde_json::Value,
+    element: &serde_json::Value,
+    metadata: &RecordMetadata,
+    mapping: &Mapping,
+) -> Result<Operation> {
+    let operation = match &mapping.operation_from {
+        Some(from) => {
+            let value = select_text(&from.json_key, record, element, metadata);
+            value
+                .as_ref()
+                .and_then(|value| from.values.get(value))
+                .ok_or_else(|| {
+                    eyre!(
+                        "no operation for {} value: {}",
+                        from.json_key,
+                        value.as_deref().unwrap_or("null")
+                    )
+                })?
+        }
+        None => &mapping.operation,
+    };
+
+    if *operation != MappingOperation::Call && mapping.table.is_empty() {
+        return Err(eyre!("table can't be empty"));
+    }
+
+    if *operation != MappingOperation::Call && mapping.columns.is_empty() {
+        return Err(eyre!("map-columns can't be empty"));
     }
 
-    let op = match mapping.operation {
+    let op = match operation {
         MappingOperation::Insert => Operation::Insert(Insert {
             table: mapping.table.clone(),
-            values,
+            values: map_columns(record, element, metadata, mapping)?,
         }),
         MappingOperation::Upsert => {
             if mapping.unique_columns.is_empty() {
@@ -39,20 +245,200 @@ pub(crate) fn transform(record: serde_json::Value, mapping: &Mapping) -> Result<
 
             Operation::Upsert(Upsert {
                 table: mapping.table.clone(),
-                values,
+                values: map_columns(record, element, metadata, mapping)?,
                 uniq_idx: mapping.unique_columns.join(","),
             })
         }
+        MappingOperation::Call => {
+            let routine = mapping
+                .routine
+                .as_ref()
+                .ok_or_else(|| eyre!("routine can't be empty when doing call"))?;
+
+            let mut values = map_columns(record, element, metadata, mapping)?;
+            let mut args = Vec::with_capacity(routine.args.len());
+            for arg in routine.args.iter() {
+                let position = values
+                    .iter()
+                    .position(|v| v.column.eq(arg))
+                    .ok_or_else(|| eyre!("routine argument is not mapped: {}", arg))?;
+                args.push(values.swap_remove(position));
+            }
+
+            Operation::Call(Call {
+                routine: routine.name.clone(),
+                args,
+                kind: routine.kind.into(),
+            })
+        }
+        // the key is enough, the other columns may be missing in the record
+        MappingOperation::Delete => transform_delete(record, element, metadata, mapping)?,
     };
 
     Ok(op)
 }
 
+/// Maps only the unique columns, rows are deleted by the key.
+fn transform_delete(
+    record: &serde_json::Value,
+    element: &serde_json::Value,
+    metadata: &RecordMetadata,
+    mapping: &Mapping,
+) -> Result<Operation> {
+    if mapping.unique_columns.is_empty() {
+        return Err(eyre!("unique-columns can't be empty when doing delete"));
+    }
+
+    let mut key = Vec::with_capacity(mapping.unique_columns.len());
+    for name in mapping.unique_columns.iter() {
+        let column = mapping
+            .columns
+            .get(name)
+            .ok_or_else(|| eyre!("unique column is not mapped: {}", name))?;
+        let value = map_column(record, element, metadata, name, column)?;
+        if is_missing(&value) {
+            return Err(eyre!("unique column {} is missing in the record", name));
+        }
+        key.push(value);
+    }
+
+    Ok(Operation::Delete(Delete {
+        table: mapping.table.clone(),
+        values: key,
+    }))
+}
+
+/// Maps the columns of the element, relative paths select from the element.
+fn map_columns(
+    record: &serde_json::Value,
+    element: &serde_json::Value,
+    metadata: &RecordMetadata,
+    mapping: &Mapping,
+) -> Result<Vec<Value>> {
+    mapping
+        .columns
+        .iter()
+        .map(|(name, column)| map_column(record, element, metadata, name, column))
+        .collect()
+}
+
+/// Maps the column of the element, relative paths select from the element.
+/// Expressions evaluated to `null` are handled as missing values.
+fn map_column(
+    record: &serde_json::Value,
+    element: &serde_json::Value,
+    metadata: &RecordMetadata,
+    name: &str,
+    column: &Column,
+) -> Result<Value> {
+    let (found, source): (_, &dyn fmt::Display) = match (&column.json_key, &column.expr) {
+        (Some(json_key), None) => (json_key.select_in(record, element, metadata), json_key),
+        (None, Some(expr)) => {
+            let value = expr.evaluate(record, element, metadata)?;
+            let found = (!value.is_null()).then_some(Cow::Owned(value));
+            (found, expr)
+        }
+        (Some(_), Some(_)) => {
+            return Err(eyre!("column {} can't have both json-key and expr", name))
+        }
+        // constant column
+        (None, None) if column.value.default.is_some() || column.value.generate.is_some() => {
+            (None, &name)
+        }
+        (None, None) => {
+            return Err(eyre!(
+                "column {} needs json-key, expr, default or generate",
+                name
+            ))
+        }
+    };
+    // the record timestamp is in milliseconds regardless of the format of the field
+    let epoch_millis = TimeFormat::EpochMillis;
+    let mut format = column.value.format.as_ref();
+    let mut generated = None;
+    let (raw_value, typed_value) = match found.as_deref() {
+        None => match (
+            &column.value.default,
+            column.value.generate,
+            metadata.timestamp,
+        ) {
+            (Some(default), _, _) => (default.clone(), None),
+            (None, Some(Generator::RecordTimestamp), Some(timestamp)) => {
+                if is_time_type(&column.value.type_) {
+                    format = Some(&epoch_millis);
+                    (timestamp.to_string(), None)
+                } else {
+                    format = None;
+                    (timestamp.to_string(), Some(timestamp.into()))
+                }
+            }
+            (None, Some(Generator::Now), _) => {
+                generated = Some(Generated::Now);
+                (String::new(), None)
+            }
+            (None, Some(Generator::UuidV4), _) => {
+                generated = Some(Generated::UuidV4);
+                (String::new(), None)
+            }
+            (None, Some(Generator::UuidV7), _) => {
+                generated = Some(Generated::UuidV7);
+                (String::new(), None)
+            }
+            (None, _, _) => {
+                if column.value.required {
+                    return Err(eyre!("Missing required field: {}", source));
+                }
+                (String::new(), None)
+            }
+        },
+        Some(serde_json::Value::String(text)) if column.value.format.is_some() => {
+            (text.clone(), None)
+        }
+        Some(found) if column.value.format.is_some() => (serde_json::to_string(found)?, None),
+        // the text is kept for sinks that don't read typed values
+        Some(found @ serde_json::Value::String(text)) => (text.clone(), Some(found.clone())),
+        Some(found) => (found.to_string(), Some(found.clone())),
+    };
+    let raw_value = match format {
+        Some(format) if !raw_value.is_empty() => normalize(&raw_value, format, &column.value.type_)
+            .wrap_err_with(|| format!("Invalid date or time value: {}", source))?,
+        _ => raw_value,
+    };
+    Ok(Value {
+        column: name.to_string(),
+        raw_value,
+        typed_value,
+        type_: Type::from(column.value.type_.clone()),
+        cast: column.value.cast.clone().map(Into::into),
+        encoding: column.value.encoding.map(Into::into),
+        generated,
+    })
+}
+
+/// Missing or `null` values can't identify a row.
+fn is_missing(value: &Value) -> bool {
+    match &value.typed_value {
+        Some(typed) => typed.is_null(),
+        None => value.raw_value.is_empty() && value.generated.is_none(),
+    }
+}
+
+fn is_time_type(type_: &ValueType) -> bool {
+    matches!(
+        type_,
+        ValueType::Timestamp | ValueType::TimestampTz | ValueType::Date | ValueType::Time
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    fn transform(record: serde_json::Value, mapping: &Mapping) -> Result<Operation> {
+        transform_element(&record, &record, &RecordMetadata::default(), mapping)
+    }
+
     #[test]
     fn test_transform_upsert_multiple_unique_columns() {
         // given
@@ -87,7 +473,11 @@ mod tests {
                 values: vec![Value {
                     column: "body".to_string(),
                     raw_value: "{\"key\":\"value\"}".to_string(),
-                    type_: Type::Json
+                    type_: Type::Json,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: Some(json!({"key": "value"})),
                 }]
             })
         );
@@ -127,26 +517,41 @@ mod tests {
                 values: vec![Value {
                     column: "body".to_string(),
                     raw_value: "{\"key\":\"value\"}".to_string(),
-                    type_: Type::Json
+                    type_: Type::Json,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: Some(json!({"key": "value"})),
                 }]
             })
         );
     }
 
     #[test]
-    fn test_pass_whole_object() {
+    fn test_transform_call() {
         // given
         let input = json!({
-            "key": "value"
+            "customer": {"id": 7},
+            "amount": 9.99
         });
 
         let mapping: Mapping = serde_json::from_value(json!({
-            "table" : "test_table",
+            "operation": "call",
+            "routine": {
+                "name": "apply_payment",
+                "args": ["customer", "amount"]
+            },
             "map-columns": {
-                "body" : {
-                    "json-key": "$",
+                "amount" : {
+                    "json-key": "amount",
                     "value": {
-                        "type": "json"
+                        "type": "numeric"
+                    }
+                },
+                "customer" : {
+                    "json-key": "customer.id",
+                    "value": {
+                        "type": "int"
                     }
                 }
             }
@@ -159,32 +564,51 @@ mod tests {
         // then
         assert_eq!(
             operation,
-            Operation::Insert(Insert {
-                table: "test_table".to_string(),
-                values: vec![Value {
-                    column: "body".to_string(),
-                    raw_value: "{\"key\":\"value\"}".to_string(),
-                    type_: Type::Json
-                }]
+            Operation::Call(Call {
+                routine: "apply_payment".to_string(),
+                args: vec![
+                    Value {
+                        column: "customer".to_string(),
+                        raw_value: "7".to_string(),
+                        type_: Type::Int,
+                        cast: None,
+                        encoding: None,
+                        generated: None,
+                        typed_value: Some(json!(7)),
+                    },
+                    Value {
+                        column: "amount".to_string(),
+                        raw_value: "9.99".to_string(),
+                        type_: Type::Numeric,
+                        cast: None,
+                        encoding: None,
+                        generated: None,
+                        typed_value: Some(json!(9.99)),
+                    }
+                ],
+                kind: fluvio_model_sql::RoutineKind::Function,
             })
         );
     }
 
     #[test]
-    fn test_required_field_missed() {
+    fn test_transform_call_unmapped_argument() {
         // given
         let input = json!({
-            "key": "value"
+            "amount": 9.99
         });
 
         let mapping: Mapping = serde_json::from_value(json!({
-            "table" : "test_table",
+            "operation": "call",
+            "routine": {
+                "name": "apply_payment",
+                "args": ["customer", "amount"]
+            },
             "map-columns": {
-                "body" : {
-                    "json-key": ".wrong_key",
+                "amount" : {
+                    "json-key": "amount",
                     "value": {
-                        "type": "text",
-                        "required": true
+                        "type": "numeric"
                     }
                 }
             }
@@ -195,15 +619,151 @@ mod tests {
         let res = transform(input, &mapping);
 
         // then
-        assert!(res.is_err());
         assert_eq!(
             res.unwrap_err().to_string(),
-            "Missing required field: .wrong_key".to_string()
+            "routine argument is not mapped: customer".to_string()
+        );
+    }
+
+    fn make_action_mapping() -> Mapping {
+        serde_json::from_value(json!({
+            "table": "users",
+            "operation-from": {
+                "json-key": "$.action",
+                "values": {
+                    "insert": "insert",
+                    "upsert": "upsert",
+                    "delete": "delete"
+                }
+            },
+            "unique-columns": ["id"],
+            "map-columns": {
+                "id": { "json-key": "$.id", "value": { "type": "int" } },
+                "name": { "json-key": "$.name", "value": { "type": "text" } }
+            }
+        }))
+        .expect("valid mapping")
+    }
+
+    #[test]
+    fn test_operation_from_record() {
+        // given
+        let mapping = make_action_mapping();
+
+        // when
+        let insert = transform(json!({"action": "insert", "id": 1, "name": "a"}), &mapping)
+            .expect("transformation succeeded");
+        let upsert = transform(json!({"action": "upsert", "id": 1, "name": "b"}), &mapping)
+            .expect("transformation succeeded");
+        let delete = transform(json!({"action": "delete", "id": 1}), &mapping)
+            .expect("transformation succeeded");
+
+        // then
+        assert!(matches!(insert, Operation::Insert(_)));
+        let Operation::Upsert(upsert) = upsert else {
+            panic!("expected upsert");
+        };
+        assert_eq!(upsert.uniq_idx, "id");
+        assert_eq!(
+            delete,
+            Operation::Delete(Delete {
+                table: "users".to_string(),
+                values: vec![Value {
+                    column: "id".to_string(),
+                    raw_value: "1".to_string(),
+                    typed_value: Some(json!(1)),
+                    type_: Type::Int,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                }],
+            })
         );
     }
 
     #[test]
-    fn test_default_value() {
+    fn test_operation_from_unknown_value() {
+        // given
+        let mapping = make_action_mapping();
+
+        // when
+        let unknown = transform(json!({"action": "merge", "id": 1}), &mapping);
+        let missing = transform(json!({"id": 1}), &mapping);
+
+        // then
+        assert_eq!(
+            unknown.unwrap_err().to_string(),
+            "no operation for $.action value: merge"
+        );
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "no operation for $.action value: null"
+        );
+    }
+
+    #[test]
+    fn test_transform_delete_maps_only_key() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "users",
+            "operation-from": {
+                "json-key": "$.action",
+                "values": { "insert": "insert", "delete": "delete" }
+            },
+            "unique-columns": ["id"],
+            "map-columns": {
+                "id": { "json-key": "$.id", "value": { "type": "int" } },
+                "name": { "json-key": "$.name", "value": { "type": "text", "required": true } }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let delete = transform(json!({"action": "delete", "id": 1}), &mapping);
+        let missing_key = transform(json!({"action": "delete", "name": "a"}), &mapping);
+        let null_key = transform(json!({"action": "delete", "id": null}), &mapping);
+
+        // then
+        let Operation::Delete(delete) = delete.expect("transformation succeeded") else {
+            panic!("expected delete");
+        };
+        assert_eq!(delete.values.len(), 1);
+        assert_eq!(delete.values[0].column, "id");
+        assert_eq!(
+            missing_key.unwrap_err().to_string(),
+            "unique column id is missing in the record"
+        );
+        assert_eq!(
+            null_key.unwrap_err().to_string(),
+            "unique column id is missing in the record"
+        );
+    }
+
+    #[test]
+    fn test_transform_delete_unmapped_unique_column() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "users",
+            "operation": "delete",
+            "unique-columns": ["id"],
+            "map-columns": {
+                "name": { "json-key": "$.name", "value": { "type": "text" } }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let res = transform(json!({"name": "a"}), &mapping);
+
+        // then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "unique column is not mapped: id"
+        );
+    }
+
+    #[test]
+    fn test_pass_whole_object() {
         // given
         let input = json!({
             "key": "value"
@@ -213,11 +773,9 @@ mod tests {
             "table" : "test_table",
             "map-columns": {
                 "body" : {
-                    "json-key": ".wrong_key",
+                    "json-key": "$",
                     "value": {
-                        "type": "text",
-                        "default": "some_value",
-                        "required": true
+                        "type": "json"
                     }
                 }
             }
@@ -234,13 +792,1007 @@ mod tests {
                 table: "test_table".to_string(),
                 values: vec![Value {
                     column: "body".to_string(),
-                    raw_value: "some_value".to_string(),
-                    type_: Type::Text
+                    raw_value: "{\"key\":\"value\"}".to_string(),
+                    type_: Type::Json,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: Some(json!({"key": "value"})),
                 }]
             })
         );
     }
 
+    #[test]
+    fn test_json_path_multiple_matches() {
+        // given
+        let input = json!({
+            "items": [{"qty": 2}, {"qty": 0}, {"qty": 5}]
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "test_table",
+            "map-columns": {
+                "quantities" : {
+                    "json-key": "$.items[?(@.qty > 0)].qty",
+                    "value": {
+                        "type": "int[]"
+                    }
+                },
+                "missing" : {
+                    "json-key": "$.items[*].sku",
+                    "value": {
+                        "type": "text[]",
+                        "default": "[]"
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operation = transform(input, &mapping).expect("transformation succeeded");
+
+        // then
+        let Operation::Insert(Insert { values, .. }) = operation else {
+            panic!("expected insert operation");
+        };
+        let quantities = values.iter().find(|v| v.column == "quantities").unwrap();
+        let missing = values.iter().find(|v| v.column == "missing").unwrap();
+        assert_eq!(quantities.typed_value, Some(json!([2, 5])));
+        assert_eq!(missing.raw_value, "[]");
+    }
+
+    #[test]
+    fn test_explode_array() {
+        // given
+        let input = json!({
+            "order_id": 7,
+            "items": [{"sku": "a", "qty": 2}, {"sku": "b", "qty": 1}]
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "order_items",
+            "explode": "$.items",
+            "map-columns": {
+                "order_id" : {
+                    "json-key": "order_id",
+                    "value": {
+                        "type": "int"
+                    }
+                },
+                "sku" : {
+                    "json-key": "@.sku",
+                    "value": {
+                        "type": "text"
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operations = transform_all(&input, &RecordMetadata::default(), &mapping)
+            .expect("transformation succeeded");
+
+        // then
+        let rows: Vec<_> = operations
+            .into_iter()
+            .map(|operation| {
+                let Operation::Insert(Insert { mut values, .. }) = operation else {
+                    panic!("expected insert operation");
+                };
+                values.sort_by(|a, b| a.column.cmp(&b.column));
+                values
+                    .into_iter()
+                    .map(|v| v.typed_value.unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![vec![json!(7), json!("a")], vec![json!(7), json!("b")]]
+        );
+    }
+
+    #[test]
+    fn test_explode_missing_or_invalid_array() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "order_items",
+            "explode": "$.items",
+            "map-columns": {
+                "sku" : {
+                    "json-key": "@.sku",
+                    "value": {
+                        "type": "text"
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let missing = transform_all(&json!({}), &RecordMetadata::default(), &mapping);
+        let empty = transform_all(&json!({"items": []}), &RecordMetadata::default(), &mapping);
+        let invalid = transform_all(
+            &json!({"items": {"sku": "a"}}),
+            &RecordMetadata::default(),
+            &mapping,
+        );
+
+        // then
+        assert!(missing.expect("no operations").is_empty());
+        assert!(empty.expect("no operations").is_empty());
+        assert_eq!(
+            invalid.unwrap_err().to_string(),
+            "exploded value is not an array: $.items"
+        );
+    }
+
+    #[test]
+    fn test_multiple_tables_in_transaction() {
+        // given
+        let input = json!({
+            "id": 7,
+            "customer": "acme",
+            "items": [{"sku": "a"}, {"sku": "b"}]
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "transaction": true,
+            "tables": [
+                {
+                    "table": "orders",
+                    "operation": "upsert",
+                    "unique-columns": ["id"],
+                    "map-columns": {
+                        "id": { "json-key": "id", "value": { "type": "int" } },
+                        "customer": { "json-key": "customer", "value": { "type": "text" } }
+                    }
+                },
+                {
+                    "table": "order_items",
+                    "explode": "$.items",
+                    "map-columns": {
+                        "sku": { "json-key": "@.sku", "value": { "type": "text" } }
+                    }
+                }
+            ]
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operations = transform_all(&input, &RecordMetadata::default(), &mapping)
+            .expect("transformation succeeded");
+
+        // then
+        let [Operation::Transaction(operations)] = operations.as_slice() else {
+            panic!("expected one transaction");
+        };
+        let tables: Vec<_> = operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::Upsert(upsert) => upsert.table.as_str(),
+                Operation::Insert(insert) => insert.table.as_str(),
+                other => panic!("unexpected operation: {other:?}"),
+            })
+            .collect();
+        assert_eq!(tables, vec!["orders", "order_items", "order_items"]);
+    }
+
+    #[test]
+    fn test_multiple_tables_without_transaction() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "tables": [
+                {
+                    "table": "a",
+                    "map-columns": { "id": { "json-key": "id", "value": { "type": "int" } } }
+                },
+                {
+                    "table": "b",
+                    "map-columns": { "id": { "json-key": "id", "value": { "type": "int" } } }
+                }
+            ]
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operations = transform_all(&json!({"id": 1}), &RecordMetadata::default(), &mapping)
+            .expect("transformation succeeded");
+
+        // then
+        assert_eq!(operations.len(), 2);
+        assert!(operations
+            .iter()
+            .all(|operation| matches!(operation, Operation::Insert(_))));
+    }
+
+    #[test]
+    fn test_empty_columns() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "test_table"
+        }))
+        .expect("valid mapping");
+
+        // when
+        let res = transform_all(&json!({"id": 1}), &RecordMetadata::default(), &mapping);
+
+        // then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "map-columns can't be empty".to_string()
+        );
+    }
+
+    fn make_routed_mapping(fallback: serde_json::Value) -> Mapping {
+        serde_json::from_value(json!({
+            "discriminator": "$.type",
+            "routes": {
+                "user_created": {
+                    "table": "users",
+                    "map-columns": {
+                        "id": { "json-key": "$.id", "value": { "type": "int" } },
+                        "name": { "json-key": "$.name", "value": { "type": "text" } }
+                    }
+                },
+                "user_deleted": {
+                    "table": "deleted_users",
+                    "map-columns": {
+                        "id": { "json-key": "$.id", "value": { "type": "int" } }
+                    }
+                }
+            },
+            "fallback": fallback
+        }))
+        .expect("valid mapping")
+    }
+
+    #[test]
+    fn test_route_by_discriminator() {
+        // given
+        let mapping = make_routed_mapping(json!("error"));
+
+        // when
+        let created = transform_all(
+            &json!({"type": "user_created", "id": 1, "name": "Alice"}),
+            &RecordMetadata::default(),
+            &mapping,
+        )
+        .expect("transformation succeeded");
+        let deleted = transform_all(
+            &json!({"type": "user_deleted", "id": 1}),
+            &RecordMetadata::default(),
+            &mapping,
+        )
+        .expect("transformation succeeded");
+
+        // then
+        let [Operation::Insert(created)] = created.as_slice() else {
+            panic!("expected one insert");
+        };
+        let [Operation::Insert(deleted)] = deleted.as_slice() else {
+            panic!("expected one insert");
+        };
+        assert_eq!(created.table, "users");
+        assert_eq!(created.values.len(), 2);
+        assert_eq!(deleted.table, "deleted_users");
+        assert_eq!(deleted.values.len(), 1);
+    }
+
+    #[test]
+    fn test_route_fallback() {
+        // given
+        let record = json!({"type": "user_updated", "id": 1});
+        let error = make_routed_mapping(json!("error"));
+        let skip = make_routed_mapping(json!("skip"));
+        let default = make_routed_mapping(json!({
+            "mapping": {
+                "table": "events",
+                "map-columns": {
+                    "body": { "json-key": "$", "value": { "type": "json" } }
+                }
+            }
+        }));
+
+        // when
+        let error = transform_all(&record, &RecordMetadata::default(), &error);
+        let skip = transform_all(&record, &RecordMetadata::default(), &skip)
+            .expect("transformation succeeded");
+        let default = transform_all(&record, &RecordMetadata::default(), &default)
+            .expect("transformation succeeded");
+        let missing = transform_all(
+            &json!({"id": 1}),
+            &RecordMetadata::default(),
+            &make_routed_mapping(json!("error")),
+        );
+
+        // then
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "no mapping for discriminator $.type value: user_updated"
+        );
+        assert!(skip.is_empty());
+        let [Operation::Insert(default)] = default.as_slice() else {
+            panic!("expected one insert");
+        };
+        assert_eq!(default.table, "events");
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "no mapping for discriminator $.type value: null"
+        );
+    }
+
+    fn make_cdc_mapping() -> Mapping {
+        serde_json::from_value(json!({
+            "table": "users",
+            "cdc": { "format": "debezium", "primary-key": ["id"] },
+            "map-columns": {
+                "id": { "json-key": "@.id", "value": { "type": "int" } },
+                "name": { "json-key": "@.name", "value": { "type": "text", "required": true } },
+                "source_ts": { "json-key": "$.source.ts_ms", "value": { "type": "bigint" } }
+            }
+        }))
+        .expect("valid mapping")
+    }
+
+    #[test]
+    fn test_cdc_tombstone() {
+        // given
+        let cdc = json!({
+            "table": "users",
+            "cdc": { "format": "debezium", "primary-key": ["id"] },
+            "map-columns": {
+                "id": { "json-key": "@.id", "value": { "type": "int" } }
+            }
+        });
+        let routed: Mapping = serde_json::from_value(json!({
+            "discriminator": "$.source.table",
+            "routes": { "users": cdc }
+        }))
+        .expect("valid mapping");
+        let plain = make_routed_mapping(json!("skip"));
+
+        // when
+        let top_level = transform_value(b"", &RecordMetadata::default(), &make_cdc_mapping());
+        let nested = transform_value(b"", &RecordMetadata::default(), &routed);
+        let not_cdc = transform_value(b"", &RecordMetadata::default(), &plain);
+
+        // then
+        assert!(top_level.expect("tombstone skipped").is_empty());
+        assert!(nested.expect("tombstone skipped").is_empty());
+        assert!(not_cdc.is_err());
+    }
+
+    #[test]
+    fn test_cdc_debezium_upsert() {
+        // given
+        let mapping = make_cdc_mapping();
+
+        for op in ["c", "u", "r"] {
+            let record = json!({
+                "before": null,
+                "after": { "id": 1, "name": "Alice" },
+                "source": { "ts_ms": 1700000000000u64 },
+                "op": op
+            });
+
+            // when
+            let operations = transform_all(&record, &RecordMetadata::default(), &mapping)
+                .expect("transformation succeeded");
+
+            // then
+            let [Operation::Upsert(upsert)] = operations.as_slice() else {
+                panic!("expected one upsert");
+            };
+            assert_eq!(upsert.table, "users");
+            assert_eq!(upsert.uniq_idx, "id");
+            let mut values: Vec<_> = upsert
+                .values
+                .iter()
+                .map(|v| (v.column.as_str(), v.typed_value.clone()))
+                .collect();
+            values.sort_by_key(|(column, _)| *column);
+            assert_eq!(
+                values,
+                vec![
+                    ("id", Some(json!(1))),
+                    ("name", Some(json!("Alice"))),
+                    ("source_ts", Some(json!(1700000000000u64)))
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cdc_debezium_delete() {
+        // given
+        let mapping = make_cdc_mapping();
+        let record = json!({
+            "schema": {},
+            "payload": {
+                "before": { "id": 1 },
+                "after": null,
+                "op": "d"
+            }
+        });
+
+        // when
+        let operations = transform_all(&record, &RecordMetadata::default(), &mapping)
+            .expect("transformation succeeded");
+
+        // then
+        assert_eq!(
+            operations,
+            vec![Operation::Delete(Delete {
+                table: "users".to_string(),
+                values: vec![Value {
+                    column: "id".to_string(),
+                    raw_value: "1".to_string(),
+                    typed_value: Some(json!(1)),
+                    type_: Type::Int,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                }],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_cdc_debezium_truncate_and_skipped_events() {
+        // given
+        let mapping = make_cdc_mapping();
+
+        // when
+        let truncate = transform_all(&json!({"op": "t"}), &RecordMetadata::default(), &mapping);
+        let tombstone = transform_all(
+            &serde_json::Value::Null,
+            &RecordMetadata::default(),
+            &mapping,
+        );
+        let message = transform_all(&json!({"op": "m"}), &RecordMetadata::default(), &mapping);
+
+        // then
+        assert_eq!(
+            truncate.expect("transformation succeeded"),
+            vec![Operation::Truncate(Truncate {
+                table: "users".to_string()
+            })]
+        );
+        assert!(tombstone.expect("transformation succeeded").is_empty());
+        assert!(message.expect("transformation succeeded").is_empty());
+    }
+
+    #[test]
+    fn test_cdc_debezium_invalid_events() {
+        // given
+        let mapping = make_cdc_mapping();
+
+        // when
+        let no_op = transform_all(
+            &json!({"after": {"id": 1}}),
+            &RecordMetadata::default(),
+            &mapping,
+        );
+        let unknown_op = transform_all(&json!({"op": "x"}), &RecordMetadata::default(), &mapping);
+        let no_after = transform_all(
+            &json!({"op": "c", "after": null}),
+            &RecordMetadata::default(),
+            &mapping,
+        );
+        let no_key = transform_all(
+            &json!({"op": "d", "before": {"name": "Alice"}}),
+            &RecordMetadata::default(),
+            &mapping,
+        );
+
+        // then
+        assert_eq!(
+            no_op.unwrap_err().to_string(),
+            "change event has no op field"
+        );
+        assert_eq!(
+            unknown_op.unwrap_err().to_string(),
+            "unsupported change event op: x"
+        );
+        assert_eq!(
+            no_after.unwrap_err().to_string(),
+            "change event with op c has no after image"
+        );
+        assert_eq!(
+            no_key.unwrap_err().to_string(),
+            "primary key column id is missing in the before image"
+        );
+    }
+
+    #[test]
+    fn test_record_metadata_columns() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "events",
+            "map-columns": {
+                "tenant": { "json-key": "@key.tenant", "value": { "type": "text" } },
+                "source_offset": { "json-key": "@offset", "value": { "type": "bigint" } },
+                "produced_at": {
+                    "json-key": "@timestamp",
+                    "value": { "type": "timestamptz", "format": "epoch-ms" }
+                }
+            }
+        }))
+        .expect("valid mapping");
+        let metadata = RecordMetadata {
+            key: Some(r#"{"tenant": "acme"}"#.to_string()),
+            offset: Some(42),
+            partition: None,
+            timestamp: Some(1700000000000),
+        };
+
+        // when
+        let operations =
+            transform_all(&json!({}), &metadata, &mapping).expect("transformation succeeded");
+
+        // then
+        let [Operation::Insert(insert)] = operations.as_slice() else {
+            panic!("expected one insert");
+        };
+        let mut values: Vec<_> = insert
+            .values
+            .iter()
+            .map(|v| {
+                (
+                    v.column.as_str(),
+                    v.raw_value.as_str(),
+                    v.typed_value.clone(),
+                )
+            })
+            .collect();
+        values.sort_by_key(|(column, _, _)| *column);
+        assert_eq!(
+            values,
+            vec![
+                ("produced_at", "2023-11-14T22:13:20Z", None),
+                ("source_offset", "42", Some(json!(42))),
+                ("tenant", "acme", Some(json!("acme")))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_computed_columns() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "orders",
+            "map-columns": {
+                "full_name": { "expr": "concat(first, ' ', last)", "value": { "type": "text" } },
+                "total": { "expr": "price * qty", "value": { "type": "numeric" } },
+                "nickname": {
+                    "expr": "coalesce(nick, missing)",
+                    "value": { "type": "text", "default": "none" }
+                }
+            }
+        }))
+        .expect("valid mapping");
+        let input = json!({"first": "Ada", "last": "Lovelace", "price": 2.5, "qty": 4});
+
+        // when
+        let operation = transform(input, &mapping).expect("transformation succeeded");
+
+        // then
+        let Operation::Insert(insert) = operation else {
+            panic!("expected insert");
+        };
+        let mut values: Vec<_> = insert
+            .values
+            .iter()
+            .map(|v| {
+                (
+                    v.column.as_str(),
+                    v.raw_value.as_str(),
+                    v.typed_value.clone(),
+                )
+            })
+            .collect();
+        values.sort_by_key(|(column, _, _)| *column);
+        assert_eq!(
+            values,
+            vec![
+                ("full_name", "Ada Lovelace", Some(json!("Ada Lovelace"))),
+                ("nickname", "none", None),
+                ("total", "10", Some(json!(10)))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_source() {
+        // given
+        let both: Mapping = serde_json::from_value(json!({
+            "table": "t",
+            "map-columns": {
+                "id": { "json-key": "id", "expr": "id + 1", "value": { "type": "int" } }
+            }
+        }))
+        .expect("valid mapping");
+        let none: Mapping = serde_json::from_value(json!({
+            "table": "t",
+            "map-columns": {
+                "id": { "value": { "type": "int" } }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let both = transform(json!({"id": 1}), &both);
+        let none = transform(json!({"id": 1}), &none);
+
+        // then
+        assert_eq!(
+            both.unwrap_err().to_string(),
+            "column id can't have both json-key and expr"
+        );
+        assert_eq!(
+            none.unwrap_err().to_string(),
+            "column id needs json-key, expr, default or generate"
+        );
+    }
+
+    #[test]
+    fn test_generated_and_constant_columns() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "events",
+            "map-columns": {
+                "id": { "json-key": "id", "value": { "type": "uuid", "generate": "uuid-v7" } },
+                "ingested_at": { "value": { "type": "timestamptz", "generate": "now" } },
+                "produced_at": {
+                    "json-key": "produced_at",
+                    "value": { "type": "timestamp", "format": "rfc3339", "generate": "record-timestamp" }
+                },
+                "produced_ms": { "value": { "type": "bigint", "generate": "record-timestamp" } },
+                "source": { "value": { "type": "text", "default": "fluvio" } }
+            }
+        }))
+        .expect("valid mapping");
+        let metadata = RecordMetadata {
+            timestamp: Some(1700000000000),
+            ..Default::default()
+        };
+
+        // when
+        let operations =
+            transform_all(&json!({}), &metadata, &mapping).expect("transformation succeeded");
+
+        // then
+        let [Operation::Insert(insert)] = operations.as_slice() else {
+            panic!("expected one insert");
+        };
+        let mut values: Vec<_> = insert
+            .values
+            .iter()
+            .map(|v| {
+                (
+                    v.column.as_str(),
+                    v.raw_value.as_str(),
+                    v.typed_value.clone(),
+                    v.generated,
+                )
+            })
+            .collect();
+        values.sort_by_key(|(column, _, _, _)| *column);
+        assert_eq!(
+            values,
+            vec![
+                ("id", "", None, Some(Generated::UuidV7)),
+                ("ingested_at", "", None, Some(Generated::Now)),
+                ("produced_at", "2023-11-14 22:13:20", None, None),
+                (
+                    "produced_ms",
+                    "1700000000000",
+                    Some(json!(1700000000000i64)),
+                    None
+                ),
+                ("source", "fluvio", None, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_record_timestamp() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "events",
+            "map-columns": {
+                "id": { "json-key": "id", "value": { "type": "uuid", "generate": "uuid-v4" } },
+                "produced_at": {
+                    "json-key": "produced_at",
+                    "value": { "type": "bigint", "generate": "record-timestamp", "required": true }
+                }
+            }
+        }))
+        .expect("valid mapping");
+        let input = json!({"id": "67e55044-10b1-426f-9247-bb680e5fe0c8"});
+
+        // when
+        let res = transform(input, &mapping);
+
+        // then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Missing required field: produced_at"
+        );
+    }
+
+    #[test]
+    fn test_filter_records() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "events",
+            "where": "type != 'heartbeat' && !exists(test) && (priority >= 2 || exists(user.id))",
+            "map-columns": {
+                "type": { "json-key": "type", "value": { "type": "text" } }
+            }
+        }))
+        .expect("valid mapping");
+        let metadata = RecordMetadata::default();
+        let count = |record: serde_json::Value| {
+            transform_all(&record, &metadata, &mapping)
+                .expect("transformation succeeded")
+                .len()
+        };
+
+        // when
+        let heartbeat = count(json!({"type": "heartbeat", "priority": 3}));
+        let test = count(json!({"type": "click", "priority": 3, "test": false}));
+        let low_priority = count(json!({"type": "click", "priority": 1}));
+        let no_priority = count(json!({"type": "click"}));
+        let user = count(json!({"type": "click", "user": {"id": 7}}));
+        let important = count(json!({"type": "click", "priority": 2}));
+
+        // then
+        assert_eq!(heartbeat, 0);
+        assert_eq!(test, 0);
+        assert_eq!(low_priority, 0);
+        assert_eq!(no_priority, 0);
+        assert_eq!(user, 1);
+        assert_eq!(important, 1);
+    }
+
+    #[test]
+    fn test_filter_tables() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "tables": [
+                {
+                    "table": "orders",
+                    "map-columns": { "id": { "json-key": "id", "value": { "type": "int" } } }
+                },
+                {
+                    "table": "refunds",
+                    "filter": "refund > 0",
+                    "map-columns": { "id": { "json-key": "id", "value": { "type": "int" } } }
+                }
+            ]
+        }))
+        .expect("valid mapping");
+
+        // when
+        let without_refund = transform_all(
+            &json!({"id": 1, "refund": 0}),
+            &RecordMetadata::default(),
+            &mapping,
+        )
+        .expect("transformation succeeded");
+        let with_refund = transform_all(
+            &json!({"id": 1, "refund": 5}),
+            &RecordMetadata::default(),
+            &mapping,
+        )
+        .expect("transformation succeeded");
+
+        // then
+        assert_eq!(without_refund.len(), 1);
+        assert_eq!(with_refund.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_not_boolean() {
+        // given
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table": "events",
+            "filter": "type",
+            "map-columns": {
+                "type": { "json-key": "type", "value": { "type": "text" } }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let res = transform_all(
+            &json!({"type": "click"}),
+            &RecordMetadata::default(),
+            &mapping,
+        );
+
+        // then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "condition `type`: expected a boolean, got \"click\""
+        );
+    }
+
+    #[test]
+    fn test_invalid_json_path() {
+        // given
+        let mapping = json!({
+            "table" : "test_table",
+            "map-columns": {
+                "body" : {
+                    "json-key": "$.items[",
+                    "value": {
+                        "type": "json"
+                    }
+                }
+            }
+        });
+
+        // when
+        let res = serde_json::from_value::<Mapping>(mapping);
+
+        // then
+        assert!(res.unwrap_err().to_string().contains("invalid json path"));
+    }
+
+    #[test]
+    fn test_required_field_missed() {
+        // given
+        let input = json!({
+            "key": "value"
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "test_table",
+            "map-columns": {
+                "body" : {
+                    "json-key": ".wrong_key",
+                    "value": {
+                        "type": "text",
+                        "required": true
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let res = transform(input, &mapping);
+
+        // then
+        assert!(res.is_err());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Missing required field: .wrong_key".to_string()
+        );
+    }
+
+    #[test]
+    fn test_default_value() {
+        // given
+        let input = json!({
+            "key": "value"
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "test_table",
+            "map-columns": {
+                "body" : {
+                    "json-key": ".wrong_key",
+                    "value": {
+                        "type": "text",
+                        "default": "some_value",
+                        "required": true
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operation = transform(input, &mapping).expect("transformation succeeded");
+
+        // then
+        assert_eq!(
+            operation,
+            Operation::Insert(Insert {
+                table: "test_table".to_string(),
+                values: vec![Value {
+                    column: "body".to_string(),
+                    raw_value: "some_value".to_string(),
+                    type_: Type::Text,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_time_format() {
+        // given
+        let input = json!({
+            "created": 1704103200123i64,
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "test_table",
+            "map-columns": {
+                "created" : {
+                    "json-key": "created",
+                    "value": {
+                        "type": "timestamp",
+                        "format": "epoch-ms"
+                    }
+                },
+                "updated" : {
+                    "json-key": "updated",
+                    "value": {
+                        "type": "timestamp",
+                        "format": "rfc3339"
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let operation = transform(input, &mapping).expect("transformation succeeded");
+
+        // then
+        let Operation::Insert(Insert { values, .. }) = operation else {
+            panic!("expected insert operation");
+        };
+        let created = values.iter().find(|v| v.column == "created").unwrap();
+        let updated = values.iter().find(|v| v.column == "updated").unwrap();
+        assert_eq!(created.raw_value, "2024-01-01 10:00:00.123");
+        assert_eq!(created.typed_value, None);
+        assert_eq!(updated.raw_value, "");
+    }
+
+    #[test]
+    fn test_time_format_invalid_value() {
+        // given
+        let input = json!({
+            "created": "yesterday",
+        });
+
+        let mapping: Mapping = serde_json::from_value(json!({
+            "table" : "test_table",
+            "map-columns": {
+                "created" : {
+                    "json-key": "created",
+                    "value": {
+                        "type": "timestamp",
+                        "format": "rfc3339"
+                    }
+                }
+            }
+        }))
+        .expect("valid mapping");
+
+        // when
+        let res = transform(input, &mapping);
+
+        // then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid date or time value: created".to_string()
+        );
+    }
+
     #[test]
     fn test_value_two_columns() {
         // given
@@ -1,21 +1,91 @@
+use std::borrow::Cow;
 use std::str::FromStr;
 
+use anyhow::anyhow;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use itertools::Itertools;
 use rust_decimal::Decimal;
+use serde_json::Value as Json;
 use sqlx::postgres::PgArguments;
 use sqlx::query::Query;
 use sqlx::sqlite::SqliteArguments;
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::types::mac_address::MacAddress;
 use sqlx::{Database, Postgres, Sqlite};
 
-use fluvio_model_sql::{Type, Value};
+use fluvio_model_sql::{Cast, ElementType, Encoding, Generated, Type, Value};
 
 use crate::db::Db;
+use crate::ident::quote_identifier;
+use crate::interval::parse_interval;
 
 pub const NAIVE_DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
 
+/// Options of binding values to queries, set from the connector config.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BindOptions {
+    pub sqlite_big_integers: BigIntegerStorage,
+}
+
+/// How integers beyond the `i64` range are stored on SQLite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BigIntegerStorage {
+    /// Decimal text, e.g. `'18446744073709551615'`
+    #[default]
+    Text,
+    /// Big-endian two's complement bytes, 8 bytes for 64-bit and 16 bytes for 128-bit types
+    Blob,
+}
+
+/// Comma-separated Postgres placeholders for the values.
+pub(crate) fn pg_placeholders(values: &[Value]) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| pg_placeholder(i + 1, v))
+        .join(",")
+}
+
+/// Postgres placeholder of the value at the position, enum values are cast to their type
+/// and integers beyond the `i64` range to `NUMERIC`.
+pub(crate) fn pg_placeholder(position: usize, value: &Value) -> String {
+    let placeholder = match &value.type_ {
+        Type::Enum(name) => format!("${position}::{}", quote_identifier(name)),
+        Type::UnsignedBigInt | Type::Int128 | Type::UnsignedInt128 => {
+            format!("${position}::NUMERIC")
+        }
+        _ => format!("${position}"),
+    };
+    match &value.cast {
+        Some(Cast::Type(name)) => format!("{placeholder}::{name}"),
+        Some(Cast::Function(name)) => format!("{name}({placeholder})"),
+        None => placeholder,
+    }
+}
+
+/// Comma-separated SQLite placeholders for the values.
+pub(crate) fn sqlite_placeholders(values: &[Value]) -> String {
+    values.iter().map(sqlite_placeholder).join(",")
+}
+
+/// SQLite placeholder of the value.
+pub(crate) fn sqlite_placeholder(value: &Value) -> String {
+    match &value.cast {
+        Some(Cast::Type(name)) => format!("CAST(? AS {name})"),
+        Some(Cast::Function(name)) => format!("{name}(?)"),
+        None => "?".to_string(),
+    }
+}
+
 pub trait Bind<DB: Database> {
     fn bind_value<'a>(
         query: Query<'a, DB, <DB>::Arguments<'a>>,
         value: &'a Value,
+        options: &BindOptions,
     ) -> anyhow::Result<Query<'a, DB, <DB>::Arguments<'a>>>;
 }
 
@@ -23,26 +93,43 @@ impl Bind<Postgres> for Db {
     fn bind_value<'a>(
         query: Query<'a, Postgres, PgArguments>,
         value: &'a Value,
+        _options: &BindOptions,
     ) -> anyhow::Result<Query<'a, Postgres, PgArguments>> {
-        let query = match value.type_ {
-            Type::Bool => query.bind(bool::from_str(&value.raw_value)?),
-            Type::Char => query.bind(i8::from_str(&value.raw_value)?),
-            Type::SmallInt => query.bind(i16::from_str(&value.raw_value)?),
-            Type::Int => query.bind(i32::from_str(&value.raw_value)?),
-            Type::BigInt => query.bind(i64::from_str(&value.raw_value)?),
-            Type::Float => query.bind(f32::from_str(&value.raw_value)?),
-            Type::DoublePrecision => query.bind(f64::from_str(&value.raw_value)?),
-            Type::Text => query.bind(value.raw_value.as_str()),
-            Type::Bytes => query.bind(value.raw_value.as_bytes()),
-            Type::Numeric => query.bind(Decimal::from_str(&value.raw_value)?),
-            Type::Timestamp => query.bind(chrono::NaiveDateTime::parse_from_str(
-                &value.raw_value,
-                NAIVE_DATE_TIME_FORMAT,
-            )?),
-            Type::Date => query.bind(chrono::NaiveDate::from_str(&value.raw_value)?),
-            Type::Time => query.bind(chrono::NaiveTime::from_str(&value.raw_value)?),
-            Type::Uuid => query.bind(uuid::Uuid::from_str(&value.raw_value)?),
-            Type::Json => query.bind(serde_json::Value::from_str(&value.raw_value)?),
+        let raw = value_text(value);
+        let query = match (&value.type_, typed_value(value)) {
+            (Type::Bool, Some(Json::Bool(b))) => query.bind(*b),
+            (Type::TinyInt, Some(Json::Number(n))) => query.bind(i8::try_from(json_int(n)?)?),
+            (Type::SmallInt, Some(Json::Number(n))) => query.bind(i16::try_from(json_int(n)?)?),
+            (Type::Int, Some(Json::Number(n))) => query.bind(i32::try_from(json_int(n)?)?),
+            (Type::BigInt, Some(Json::Number(n))) => query.bind(json_int(n)?),
+            (Type::Float, Some(Json::Number(n))) => query.bind(json_float(n)? as f32),
+            (Type::DoublePrecision, Some(Json::Number(n))) => query.bind(json_float(n)?),
+            (Type::Json, Some(json)) if !json.is_string() => query.bind(json),
+            (Type::Bool, _) => query.bind(bool::from_str(&raw)?),
+            (Type::Char(length), _) => query.bind(pad_char(&raw, *length)?),
+            (Type::TinyInt, _) => query.bind(i8::from_str(&raw)?),
+            (Type::SmallInt, _) => query.bind(i16::from_str(&raw)?),
+            (Type::Int, _) => query.bind(i32::from_str(&raw)?),
+            (Type::BigInt, _) => query.bind(i64::from_str(&raw)?),
+            (Type::UnsignedBigInt, _) => query.bind(u64::from_str(&raw)?.to_string()),
+            (Type::Int128, _) => query.bind(i128::from_str(&raw)?.to_string()),
+            (Type::UnsignedInt128, _) => query.bind(u128::from_str(&raw)?.to_string()),
+            (Type::Float, _) => query.bind(f32::from_str(&raw)?),
+            (Type::DoublePrecision, _) => query.bind(f64::from_str(&raw)?),
+            (Type::Text, _) => query.bind(raw),
+            (Type::Bytes, _) => query.bind(decode_bytes(&raw, value.encoding)?),
+            (Type::Numeric, _) => query.bind(Decimal::from_str(&raw)?),
+            (Type::Timestamp, _) => query.bind(parse_timestamp(&raw)?),
+            (Type::TimestampTz, _) => query.bind(parse_timestamp_tz(&raw)?),
+            (Type::Date, _) => query.bind(chrono::NaiveDate::from_str(&raw)?),
+            (Type::Time, _) => query.bind(chrono::NaiveTime::from_str(&raw)?),
+            (Type::Uuid, _) => query.bind(uuid::Uuid::from_str(&raw)?),
+            (Type::Interval, _) => query.bind(parse_interval(&raw)?),
+            (Type::Inet | Type::Cidr, _) => query.bind(IpNetwork::from_str(&raw)?),
+            (Type::MacAddr, _) => query.bind(MacAddress::from_str(&raw)?),
+            (Type::Json, _) => query.bind(serde_json::Value::from_str(&raw)?),
+            (Type::Enum(_), _) => query.bind(raw),
+            (Type::Array(element), _) => bind_pg_array(query, *element, &raw)?,
         };
         Ok(query)
     }
@@ -52,27 +139,297 @@ impl Bind<Sqlite> for Db {
     fn bind_value<'a>(
         query: Query<'a, Sqlite, SqliteArguments<'a>>,
         value: &'a Value,
+        options: &BindOptions,
     ) -> anyhow::Result<Query<'a, Sqlite, SqliteArguments<'a>>> {
-        let query = match value.type_ {
-            Type::Bool => query.bind(bool::from_str(&value.raw_value)?),
-            Type::Char => query.bind(i8::from_str(&value.raw_value)?),
-            Type::SmallInt => query.bind(i16::from_str(&value.raw_value)?),
-            Type::Int => query.bind(i32::from_str(&value.raw_value)?),
-            Type::BigInt => query.bind(i64::from_str(&value.raw_value)?),
-            Type::Float => query.bind(f32::from_str(&value.raw_value)?),
-            Type::DoublePrecision => query.bind(f64::from_str(&value.raw_value)?),
-            Type::Text => query.bind(value.raw_value.as_str()),
-            Type::Bytes => query.bind(value.raw_value.as_bytes()),
-            Type::Numeric => query.bind(f64::from_str(&value.raw_value)?),
-            Type::Timestamp => query.bind(chrono::NaiveDateTime::parse_from_str(
-                &value.raw_value,
-                NAIVE_DATE_TIME_FORMAT,
-            )?),
-            Type::Date => query.bind(chrono::NaiveDate::from_str(&value.raw_value)?),
-            Type::Time => query.bind(chrono::NaiveTime::from_str(&value.raw_value)?),
-            Type::Uuid => query.bind(uuid::Uuid::from_str(&value.raw_value)?),
-            Type::Json => query.bind(serde_json::Value::from_str(&value.raw_value)?),
+        let raw = value_text(value);
+        let query = match (&value.type_, typed_value(value)) {
+            (Type::Bool, Some(Json::Bool(b))) => query.bind(*b),
+            (Type::TinyInt, Some(Json::Number(n))) => query.bind(i8::try_from(json_int(n)?)?),
+            (Type::SmallInt, Some(Json::Number(n))) => query.bind(i16::try_from(json_int(n)?)?),
+            (Type::Int, Some(Json::Number(n))) => query.bind(i32::try_from(json_int(n)?)?),
+            (Type::BigInt, Some(Json::Number(n))) => query.bind(json_int(n)?),
+            (Type::Float, Some(Json::Number(n))) => query.bind(json_float(n)? as f32),
+            (Type::DoublePrecision, Some(Json::Number(n))) => query.bind(json_float(n)?),
+            (Type::Json, Some(json)) if !json.is_string() => query.bind(json),
+            (Type::Bool, _) => query.bind(bool::from_str(&raw)?),
+            (Type::Char(length), _) => query.bind(pad_char(&raw, *length)?),
+            (Type::TinyInt, _) => query.bind(i8::from_str(&raw)?),
+            (Type::SmallInt, _) => query.bind(i16::from_str(&raw)?),
+            (Type::Int, _) => query.bind(i32::from_str(&raw)?),
+            (Type::BigInt, _) => query.bind(i64::from_str(&raw)?),
+            (Type::UnsignedBigInt, _) => {
+                let int = u64::from_str(&raw)?;
+                match options.sqlite_big_integers {
+                    BigIntegerStorage::Text => query.bind(int.to_string()),
+                    BigIntegerStorage::Blob => query.bind(int.to_be_bytes().to_vec()),
+                }
+            }
+            (Type::Int128, _) => {
+                let int = i128::from_str(&raw)?;
+                match options.sqlite_big_integers {
+                    BigIntegerStorage::Text => query.bind(int.to_string()),
+                    BigIntegerStorage::Blob => query.bind(int.to_be_bytes().to_vec()),
+                }
+            }
+            (Type::UnsignedInt128, _) => {
+                let int = u128::from_str(&raw)?;
+                match options.sqlite_big_integers {
+                    BigIntegerStorage::Text => query.bind(int.to_string()),
+                    BigIntegerStorage::Blob => query.bind(int.to_be_bytes().to_vec()),
+                }
+            }
+            (Type::Float, _) => query.bind(f32::from_str(&raw)?),
+            (Type::DoublePrecision, _) => query.bind(f64::from_str(&raw)?),
+            (Type::Text, _) => query.bind(raw),
+            (Type::Bytes, _) => query.bind(decode_bytes(&raw, value.encoding)?),
+            (Type::Numeric, _) => query.bind(f64::from_str(&raw)?),
+            (Type::Timestamp, _) => query.bind(parse_timestamp(&raw)?),
+            (Type::TimestampTz, _) => {
+                query.bind(parse_timestamp_tz(&raw)?.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            (Type::Date, _) => query.bind(chrono::NaiveDate::from_str(&raw)?),
+            (Type::Time, _) => query.bind(chrono::NaiveTime::from_str(&raw)?),
+            (Type::Uuid, _) => query.bind(uuid::Uuid::from_str(&raw)?),
+            (Type::Interval, _) => {
+                parse_interval(&raw)?;
+                query.bind(raw)
+            }
+            (Type::Inet | Type::Cidr, _) => query.bind(IpNetwork::from_str(&raw)?.to_string()),
+            (Type::MacAddr, _) => query.bind(MacAddress::from_str(&raw)?.to_string()),
+            (Type::Json, _) => query.bind(serde_json::Value::from_str(&raw)?),
+            (Type::Enum(_), _) => query.bind(raw),
+            (Type::Array(element), _) => {
+                check_array(*element, &raw)?;
+                query.bind(serde_json::Value::Array(parse_json_array(&raw)?))
+            }
         };
         Ok(query)
     }
 }
+
+/// Typed value bound without re-parsing, generated values replace it.
+fn typed_value(value: &Value) -> Option<&Json> {
+    value
+        .typed_value
+        .as_ref()
+        .filter(|_| value.generated.is_none())
+}
+
+/// Text of the value, typed values other than strings are formatted as JSON.
+pub(crate) fn value_text(value: &Value) -> Cow<'_, str> {
+    if let Some(generated) = value.generated {
+        return Cow::Owned(generate(generated, &value.type_));
+    }
+    match &value.typed_value {
+        None => Cow::Borrowed(&value.raw_value),
+        Some(Json::String(text)) => Cow::Borrowed(text),
+        Some(other) => Cow::Owned(other.to_string()),
+    }
+}
+
+/// Text of the generated value. The current time is rendered in the representation of
+/// the time types, as milliseconds since Unix epoch for 64 and 128-bit integers and numeric,
+/// and as RFC 3339 text otherwise.
+pub(crate) fn generate(generated: Generated, type_: &Type) -> String {
+    match generated {
+        Generated::UuidV4 => uuid::Uuid::new_v4().to_string(),
+        Generated::UuidV7 => uuid::Uuid::now_v7().to_string(),
+        Generated::Now => {
+            let now = Utc::now();
+            match type_ {
+                Type::Timestamp => now.naive_utc().format(NAIVE_DATE_TIME_FORMAT).to_string(),
+                Type::Date => now.date_naive().to_string(),
+                Type::Time => now.time().to_string(),
+                Type::BigInt
+                | Type::UnsignedBigInt
+                | Type::Int128
+                | Type::UnsignedInt128
+                | Type::Numeric => now.timestamp_millis().to_string(),
+                _ => now.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            }
+        }
+    }
+}
+
+fn json_int(number: &serde_json::Number) -> anyhow::Result<i64> {
+    number
+        .as_i64()
+        .ok_or_else(|| anyhow!("expected integer, got: {number}"))
+}
+
+fn json_float(number: &serde_json::Number) -> anyhow::Result<f64> {
+    number
+        .as_f64()
+        .ok_or_else(|| anyhow!("expected float, got: {number}"))
+}
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decodes bytes value from its text encoding, raw values are taken as UTF-8 bytes.
+/// Hex digits may be prefixed with `\\x` or `0x`.
+fn decode_bytes(raw: &str, encoding: Option<Encoding>) -> anyhow::Result<Vec<u8>> {
+    let bytes = match encoding.unwrap_or_default() {
+        Encoding::Raw => raw.as_bytes().to_vec(),
+        Encoding::Base64 => BASE64.decode(raw)?,
+        Encoding::Base64Url => BASE64_URL.decode(raw)?,
+        Encoding::Hex => {
+            let digits = raw
+                .strip_prefix("\\x")
+                .or_else(|| raw.strip_prefix("0x"))
+                .unwrap_or(raw);
+            hex::decode(digits)?
+        }
+    };
+    Ok(bytes)
+}
+
+/// Pads the value with spaces to the fixed length, longer values are rejected.
+fn pad_char(raw: &str, length: Option<u32>) -> anyhow::Result<String> {
+    let Some(length) = length else {
+        return Ok(raw.to_string());
+    };
+    let length = length as usize;
+    let count = raw.chars().count();
+    if count > length {
+        return Err(anyhow!(
+            "value of {count} characters is too long for char({length})"
+        ));
+    }
+    Ok(format!("{raw:<length$}"))
+}
+
+/// Parses timestamp in [`NAIVE_DATE_TIME_FORMAT`], `T` is accepted as the date and time separator.
+pub(crate) fn parse_timestamp(raw: &str) -> anyhow::Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(raw, NAIVE_DATE_TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::from_str(raw))
+        .map_err(Into::into)
+}
+
+/// Parses RFC 3339 timestamp with an offset, a space is accepted instead of `T` separator.
+pub(crate) fn parse_timestamp_tz(raw: &str) -> anyhow::Result<DateTime<Utc>> {
+    Ok(DateTime::<chrono::FixedOffset>::from_str(raw)?.with_timezone(&Utc))
+}
+
+fn bind_pg_array<'a>(
+    query: Query<'a, Postgres, PgArguments>,
+    element: ElementType,
+    raw: &str,
+) -> anyhow::Result<Query<'a, Postgres, PgArguments>> {
+    let query = match element {
+        ElementType::Bool => query.bind(parse_array::<bool>(raw)?),
+        ElementType::SmallInt => query.bind(parse_array::<i16>(raw)?),
+        ElementType::Int => query.bind(parse_array::<i32>(raw)?),
+        ElementType::BigInt => query.bind(parse_array::<i64>(raw)?),
+        ElementType::Float => query.bind(parse_array::<f32>(raw)?),
+        ElementType::DoublePrecision => query.bind(parse_array::<f64>(raw)?),
+        ElementType::Numeric => query.bind(parse_array::<Decimal>(raw)?),
+        ElementType::Text => query.bind(parse_array::<String>(raw)?),
+        ElementType::Uuid => query.bind(parse_array::<uuid::Uuid>(raw)?),
+    };
+    Ok(query)
+}
+
+/// Checks that the elements of JSON array are of the element type, as Postgres does on binding.
+fn check_array(element: ElementType, raw: &str) -> anyhow::Result<()> {
+    match element {
+        ElementType::Bool => parse_array::<bool>(raw).map(drop),
+        ElementType::SmallInt => parse_array::<i16>(raw).map(drop),
+        ElementType::Int => parse_array::<i32>(raw).map(drop),
+        ElementType::BigInt => parse_array::<i64>(raw).map(drop),
+        ElementType::Float => parse_array::<f32>(raw).map(drop),
+        ElementType::DoublePrecision => parse_array::<f64>(raw).map(drop),
+        ElementType::Numeric => parse_array::<Decimal>(raw).map(drop),
+        ElementType::Text => parse_array::<String>(raw).map(drop),
+        ElementType::Uuid => parse_array::<uuid::Uuid>(raw).map(drop),
+    }
+}
+
+/// Parses elements of JSON array, `null` elements become `None`.
+fn parse_array<T>(raw: &str) -> anyhow::Result<Vec<Option<T>>>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    parse_json_array(raw)?
+        .into_iter()
+        .map(|element| match element {
+            serde_json::Value::Null => Ok(None),
+            serde_json::Value::String(text) => Ok(Some(T::from_str(&text)?)),
+            other => Ok(Some(T::from_str(&other.to_string())?)),
+        })
+        .collect()
+}
+
+fn parse_json_array(raw: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+    match serde_json::Value::from_str(raw)? {
+        serde_json::Value::Array(elements) => Ok(elements),
+        other => Err(anyhow!("expected JSON array, got: {other}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_array() {
+        assert_eq!(
+            parse_array::<i64>("[1, \"2\", null]").unwrap(),
+            vec![Some(1), Some(2), None]
+        );
+        assert_eq!(
+            parse_array::<bool>("[true, false]").unwrap(),
+            vec![Some(true), Some(false)]
+        );
+        assert!(parse_array::<i32>("[\"a\"]").is_err());
+        assert!(parse_array::<i32>("{\"a\": 1}").is_err());
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        assert_eq!(decode_bytes("ab", None).unwrap(), b"ab");
+        assert_eq!(decode_bytes("ab", Some(Encoding::Raw)).unwrap(), b"ab");
+        assert_eq!(
+            decode_bytes("+/8=", Some(Encoding::Base64)).unwrap(),
+            [0xfb, 0xff]
+        );
+        assert_eq!(
+            decode_bytes("+/8", Some(Encoding::Base64)).unwrap(),
+            [0xfb, 0xff]
+        );
+        assert_eq!(
+            decode_bytes("-_8", Some(Encoding::Base64Url)).unwrap(),
+            [0xfb, 0xff]
+        );
+        assert_eq!(
+            decode_bytes("DEADbeef", Some(Encoding::Hex)).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            decode_bytes("\\xdeadbeef", Some(Encoding::Hex)).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            decode_bytes("0xdeadbeef", Some(Encoding::Hex)).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert!(decode_bytes("-_8", Some(Encoding::Base64)).is_err());
+        assert!(decode_bytes("xyz", Some(Encoding::Hex)).is_err());
+    }
+
+    #[test]
+    fn test_pad_char() {
+        assert_eq!(pad_char("A", Some(3)).unwrap(), "A  ");
+        assert_eq!(pad_char("ÄB", Some(2)).unwrap(), "ÄB");
+        assert_eq!(pad_char("ABC", None).unwrap(), "ABC");
+        assert!(pad_char("ABCD", Some(3)).is_err());
+    }
+}
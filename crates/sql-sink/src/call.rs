@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+use sqlx::{Database, Postgres, Sqlite};
+
+use fluvio_model_sql::{RoutineKind, Value};
+
+use crate::bind::{pg_placeholders, sqlite_placeholders};
+use crate::db::Db;
+use crate::ident::quote_identifier;
+
+pub trait Call<DB: Database> {
+    fn call_query(routine: &str, kind: RoutineKind, args: &[Value]) -> anyhow::Result<String>;
+}
+
+impl Call<Postgres> for Db {
+    fn call_query(routine: &str, kind: RoutineKind, args: &[Value]) -> anyhow::Result<String> {
+        let routine = quote_identifier(routine);
+        let args_clause = pg_placeholders(args);
+        let keyword = match kind {
+            RoutineKind::Function => "SELECT",
+            RoutineKind::Procedure => "CALL",
+        };
+        Ok(format!("{keyword} {routine}({args_clause})"))
+    }
+}
+
+impl Call<Sqlite> for Db {
+    fn call_query(routine: &str, kind: RoutineKind, args: &[Value]) -> anyhow::Result<String> {
+        match kind {
+            RoutineKind::Function => {
+                let routine = quote_identifier(routine);
+                let args_clause = sqlite_placeholders(args);
+                Ok(format!("SELECT {routine}({args_clause})"))
+            }
+            RoutineKind::Procedure => Err(anyhow!("stored procedures are not supported by sqlite")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::Type;
+
+    fn make_args() -> Vec<Value> {
+        vec![
+            Value {
+                column: "customer_id".to_string(),
+                raw_value: "1".to_string(),
+                type_: Type::Int,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            },
+            Value {
+                column: "amount".to_string(),
+                raw_value: "9.99".to_string(),
+                type_: Type::Numeric,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_call_query_postgres() {
+        //when
+        let function = <Db as Call<Postgres>>::call_query(
+            "billing.apply_payment",
+            RoutineKind::Function,
+            &make_args(),
+        )
+        .unwrap();
+        let procedure = <Db as Call<Postgres>>::call_query(
+            "apply_payment",
+            RoutineKind::Procedure,
+            &make_args(),
+        )
+        .unwrap();
+
+        //then
+        assert_eq!(function, "SELECT \"billing\".\"apply_payment\"($1,$2)");
+        assert_eq!(procedure, "CALL \"apply_payment\"($1,$2)");
+    }
+
+    #[test]
+    fn test_call_query_sqlite() {
+        //when
+        let function =
+            <Db as Call<Sqlite>>::call_query("apply_payment", RoutineKind::Function, &make_args())
+                .unwrap();
+        let procedure =
+            <Db as Call<Sqlite>>::call_query("apply_payment", RoutineKind::Procedure, &make_args());
+
+        //then
+        assert_eq!(function, "SELECT \"apply_payment\"(?,?)");
+        assert!(procedure.is_err());
+    }
+}
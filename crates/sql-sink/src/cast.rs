@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+
+use fluvio_model_sql::{Cast, Operation, Value};
+
+/// Validates casts of the values against a type name syntax and operator-approved functions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CastAllowlist {
+    functions: HashSet<String>,
+}
+
+impl CastAllowlist {
+    pub(crate) fn new(functions: &[String]) -> Self {
+        Self {
+            functions: functions.iter().map(|f| f.to_lowercase()).collect(),
+        }
+    }
+
+    /// Checks casts of all values in the operation, including nested ones.
+    pub(crate) fn check(&self, operation: &Operation) -> anyhow::Result<()> {
+        match operation {
+            Operation::Insert(data) => self.check_values(&data.values),
+            Operation::Upsert(data) => self.check_values(&data.values),
+            Operation::Call(data) => self.check_values(&data.args),
+            Operation::Delete(data) => self.check_values(&data.values),
+            Operation::Statement(data) => match data.params.iter().find(|v| v.cast.is_some()) {
+                Some(value) => Err(anyhow!(
+                    "cast is not supported for statement parameters: {}",
+                    value.column
+                )),
+                None => Ok(()),
+            },
+            Operation::Transaction(operations) => {
+                operations.iter().try_for_each(|op| self.check(op))
+            }
+            Operation::Truncate(_) | Operation::SnapshotBegin(_) | Operation::SnapshotEnd(_) => {
+                Ok(())
+            }
+        }
+    }
+
+    fn check_values(&self, values: &[Value]) -> anyhow::Result<()> {
+        for value in values {
+            match &value.cast {
+                Some(Cast::Type(name)) if !is_safe_type_name(name) => {
+                    return Err(anyhow!("invalid cast type of {}: {}", value.column, name));
+                }
+                Some(Cast::Function(name)) if !self.functions.contains(&name.to_lowercase()) => {
+                    return Err(anyhow!(
+                        "cast function of {} is not in allowed-cast-functions: {}",
+                        value.column,
+                        name
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Type name made of optionally schema-qualified identifiers with type modifiers and array
+/// brackets, e.g. `geometry(Point, 4326)`, `double precision`, `public.ltree` or `int[]`.
+fn is_safe_type_name(name: &str) -> bool {
+    let mut name = name;
+    while let Some(stripped) = name.strip_suffix("[]") {
+        name = stripped;
+    }
+    let (base, modifiers) = match name.split_once('(') {
+        Some((base, rest)) => match rest.strip_suffix(')') {
+            Some(modifiers) => (base, Some(modifiers)),
+            None => return false,
+        },
+        None => (name, None),
+    };
+    let is_word = |word: &str| {
+        word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    let is_modifier = |modifier: &str| {
+        let modifier = modifier.trim();
+        !modifier.is_empty()
+            && modifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    base.trim_end().split(['.', ' ']).all(is_word)
+        && modifiers.is_none_or(|m| m.split(',').all(is_modifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::{Insert, Statement, Type};
+
+    fn make_insert(cast: Cast) -> Operation {
+        Operation::Insert(Insert {
+            table: "places".to_string(),
+            values: vec![Value {
+                cast: Some(cast),
+                ..Value::new(
+                    "location",
+                    "{\"type\":\"Point\",\"coordinates\":[1,2]}",
+                    Type::Text,
+                )
+            }],
+        })
+    }
+
+    #[test]
+    fn test_safe_type_names() {
+        for name in [
+            "geometry",
+            "geometry(Point, 4326)",
+            "double precision",
+            "public.ltree",
+            "int[]",
+            "varchar(10)[][]",
+            "numeric(10, 2)",
+        ] {
+            assert!(is_safe_type_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_unsafe_type_names() {
+        for name in [
+            "",
+            "int; DROP TABLE places",
+            "int), (SELECT 1",
+            "text -- comment",
+            "\"geometry\"",
+            "int(",
+            "1int",
+            "a..b",
+        ] {
+            assert!(!is_safe_type_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_check_casts() {
+        //given
+        let allowlist = CastAllowlist::new(&["ST_GeomFromGeoJSON".to_string()]);
+
+        //then
+        assert!(allowlist
+            .check(&make_insert(Cast::Type("geometry".to_string())))
+            .is_ok());
+        assert!(allowlist
+            .check(&make_insert(Cast::Function(
+                "st_geomfromgeojson".to_string()
+            )))
+            .is_ok());
+        assert_eq!(
+            allowlist
+                .check(&make_insert(Cast::Function("pg_sleep".to_string())))
+                .unwrap_err()
+                .to_string(),
+            "cast function of location is not in allowed-cast-functions: pg_sleep"
+        );
+        assert_eq!(
+            allowlist
+                .check(&Operation::Transaction(vec![make_insert(Cast::Type(
+                    "int; DROP TABLE places".to_string()
+                ))]))
+                .unwrap_err()
+                .to_string(),
+            "invalid cast type of location: int; DROP TABLE places"
+        );
+    }
+
+    #[test]
+    fn test_statement_params_cast() {
+        //given
+        let allowlist = CastAllowlist::new(&[]);
+        let Operation::Insert(insert) = make_insert(Cast::Type("geometry".to_string())) else {
+            unreachable!()
+        };
+
+        //when
+        let res = allowlist.check(&Operation::Statement(Statement {
+            sql: "SELECT $1".to_string(),
+            params: insert.values,
+        }));
+
+        //then
+        assert!(res.is_err());
+    }
+}
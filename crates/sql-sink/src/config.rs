@@ -2,6 +2,8 @@ use std::time::Duration;
 
 use fluvio_connector_common::{connector, secret::SecretString};
 
+use crate::bind::BigIntegerStorage;
+
 #[derive(Debug, Clone)]
 #[connector(config, name = "sql")]
 #[serde(rename_all = "kebab-case")]
@@ -16,6 +18,22 @@ pub(crate) struct SqlConfig {
     /// Minimum backoff duration to reconnect to the database
     #[serde(with = "humantime_serde", default = "default_backoff_min")]
     pub backoff_min: Duration,
+
+    /// SQL statement templates that `Statement` operations are allowed to execute
+    #[serde(default)]
+    pub allowed_statements: Vec<String>,
+
+    /// Functions that values are allowed to be cast with
+    #[serde(default)]
+    pub allowed_cast_functions: Vec<String>,
+
+    /// How integers beyond the 64-bit signed range are stored on SQLite, `text` or `blob`
+    #[serde(default)]
+    pub sqlite_big_integers: BigIntegerStorage,
+
+    /// Maximum number of operations of a table snapshot held in memory
+    #[serde(default = "default_max_snapshot_operations")]
+    pub max_snapshot_operations: usize,
 }
 
 #[inline]
@@ -27,3 +45,8 @@ fn default_backoff_max() -> Duration {
 fn default_backoff_min() -> Duration {
     Duration::from_secs(1)
 }
+
+#[inline]
+fn default_max_snapshot_operations() -> usize {
+    100_000
+}
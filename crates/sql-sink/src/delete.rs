@@ -0,0 +1,67 @@
+use fluvio_model_sql::Value;
+use itertools::Itertools;
+use sqlx::{Database, Postgres, Sqlite};
+
+use crate::bind::{pg_placeholder, sqlite_placeholder};
+use crate::db::Db;
+
+pub trait Delete<DB: Database> {
+    fn delete_query(table: &str, values: &[Value]) -> String;
+}
+
+impl Delete<Postgres> for Db {
+    fn delete_query(table: &str, values: &[Value]) -> String {
+        let where_clause = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{}={}", v.column, pg_placeholder(i + 1, v)))
+            .join(" AND ");
+        format!("DELETE FROM {table} WHERE {where_clause}")
+    }
+}
+
+impl Delete<Sqlite> for Db {
+    fn delete_query(table: &str, values: &[Value]) -> String {
+        let where_clause = values
+            .iter()
+            .map(|v| format!("{}={}", v.column, sqlite_placeholder(v)))
+            .join(" AND ");
+        format!("DELETE FROM {table} WHERE {where_clause}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::Type;
+
+    #[test]
+    fn test_delete_query_postgres() {
+        //given
+        let values = [
+            Value::new("col_0", "0", Type::Int),
+            Value::new("col_1", "1", Type::Int),
+        ];
+
+        //when
+        let sql = <Db as Delete<Postgres>>::delete_query("test_table", &values);
+
+        //then
+        assert_eq!(sql, "DELETE FROM test_table WHERE col_0=$1 AND col_1=$2");
+    }
+
+    #[test]
+    fn test_delete_query_sqlite() {
+        //given
+        let values = [
+            Value::new("col_0", "0", Type::Int),
+            Value::new("col_1", "1", Type::Int),
+        ];
+
+        //when
+        let sql = <Db as Delete<Sqlite>>::delete_query("test_table", &values);
+
+        //then
+        assert_eq!(sql, "DELETE FROM test_table WHERE col_0=? AND col_1=?");
+    }
+}
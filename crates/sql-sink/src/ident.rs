@@ -0,0 +1,79 @@
+use itertools::Itertools;
+
+/// Quotes every part of a possibly schema-qualified identifier, e.g. `billing.apply` becomes
+/// `"billing"."apply"`.
+///
+/// A part written in double quotes is taken as is, so it may contain dots, e.g.
+/// `billing."apply.v2"` becomes `"billing"."apply.v2"`. Quotes inside it are doubled as in SQL.
+pub(crate) fn quote_identifier(identifier: &str) -> String {
+    split_identifier(identifier)
+        .iter()
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .join(".")
+}
+
+/// Splits the identifier on the dots outside of the quoted parts and unquotes them.
+fn split_identifier(identifier: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut chars = identifier.chars().peekable();
+    let mut part_start = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if part_start => {
+                while let Some(c) = chars.next() {
+                    if c == '"' {
+                        if chars.peek() != Some(&'"') {
+                            break;
+                        }
+                        chars.next();
+                    }
+                    part.push(c);
+                }
+                part_start = false;
+            }
+            '.' => {
+                parts.push(std::mem::take(&mut part));
+                part_start = true;
+            }
+            c => {
+                part.push(c);
+                part_start = false;
+            }
+        }
+    }
+    parts.push(part);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_identifier() {
+        assert_eq!(quote_identifier("func"), "\"func\"");
+        assert_eq!(quote_identifier("my schema.func"), "\"my schema\".\"func\"");
+        assert_eq!(
+            quote_identifier("f\"); DROP TABLE t; --"),
+            "\"f\"\"); DROP TABLE t; --\""
+        );
+    }
+
+    #[test]
+    fn test_quote_identifier_with_quoted_parts() {
+        assert_eq!(
+            quote_identifier("billing.\"apply.v2\""),
+            "\"billing\".\"apply.v2\""
+        );
+        assert_eq!(quote_identifier("\"a.b\""), "\"a.b\"");
+        assert_eq!(
+            quote_identifier("\"say \"\"hi\"\".now\".f"),
+            "\"say \"\"hi\"\".now\".\"f\""
+        );
+        assert_eq!(
+            quote_identifier("\"x\"); DROP TABLE t; --"),
+            "\"x); DROP TABLE t; --\""
+        );
+    }
+}
@@ -3,6 +3,7 @@ use sqlx::{Database, Postgres, Sqlite};
 
 use fluvio_model_sql::Value;
 
+use crate::bind::{pg_placeholders, sqlite_placeholders};
 use crate::db::Db;
 
 pub trait Insert<DB: Database> {
@@ -12,7 +13,7 @@ pub trait Insert<DB: Database> {
 impl Insert<Postgres> for Db {
     fn insert_query(table: &str, values: &[Value]) -> String {
         let columns = values.iter().map(|v| v.column.as_str()).join(",");
-        let values_clause = (1..=values.len()).map(|i| format!("${i}")).join(",");
+        let values_clause = pg_placeholders(values);
         format!("INSERT INTO {table} ({columns}) VALUES ({values_clause})")
     }
 }
@@ -20,7 +21,92 @@ impl Insert<Postgres> for Db {
 impl Insert<Sqlite> for Db {
     fn insert_query(table: &str, values: &[Value]) -> String {
         let columns = values.iter().map(|v| v.column.as_str()).join(",");
-        let values_clause = (1..=values.len()).map(|_| "?").join(",");
+        let values_clause = sqlite_placeholders(values);
         format!("INSERT INTO {table} ({columns}) VALUES ({values_clause})")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::{Cast, Type};
+
+    fn make_values() -> Vec<Value> {
+        vec![
+            Value {
+                column: "id".to_string(),
+                raw_value: "1".to_string(),
+                type_: Type::Int,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            },
+            Value {
+                column: "status".to_string(),
+                raw_value: "shipped".to_string(),
+                type_: Type::Enum("sales.order_status".to_string()),
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_insert_query_postgres_enum_cast() {
+        //when
+        let sql = <Db as Insert<Postgres>>::insert_query("orders", &make_values());
+
+        //then
+        assert_eq!(
+            sql,
+            "INSERT INTO orders (id,status) VALUES ($1,$2::\"sales\".\"order_status\")"
+        );
+    }
+
+    #[test]
+    fn test_insert_query_casts() {
+        //given
+        let mut values = make_values();
+        values[0].cast = Some(Cast::Type("geometry(Point, 4326)".to_string()));
+        values[1].cast = Some(Cast::Function("ST_GeomFromGeoJSON".to_string()));
+
+        //when
+        let pg = <Db as Insert<Postgres>>::insert_query("places", &values);
+        let sqlite = <Db as Insert<Sqlite>>::insert_query("places", &values);
+
+        //then
+        assert_eq!(
+            pg,
+            "INSERT INTO places (id,status) VALUES ($1::geometry(Point, 4326),ST_GeomFromGeoJSON($2::\"sales\".\"order_status\"))"
+        );
+        assert_eq!(
+            sqlite,
+            "INSERT INTO places (id,status) VALUES (CAST(? AS geometry(Point, 4326)),ST_GeomFromGeoJSON(?))"
+        );
+    }
+
+    #[test]
+    fn test_insert_query_postgres_big_integer_cast() {
+        //given
+        let mut values = make_values();
+        values[0].type_ = Type::UnsignedBigInt;
+
+        //when
+        let sql = <Db as Insert<Postgres>>::insert_query("orders", &values[..1]);
+
+        //then
+        assert_eq!(sql, "INSERT INTO orders (id) VALUES ($1::NUMERIC)");
+    }
+
+    #[test]
+    fn test_insert_query_sqlite() {
+        //when
+        let sql = <Db as Insert<Sqlite>>::insert_query("orders", &make_values());
+
+        //then
+        assert_eq!(sql, "INSERT INTO orders (id,status) VALUES (?,?)");
+    }
+}
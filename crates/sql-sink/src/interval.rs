@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Context};
+use sqlx::postgres::types::PgInterval;
+
+const MICROS_PER_SECOND: f64 = 1_000_000f64;
+
+/// Parses ISO 8601 duration (`P1Y2M3DT4H5M6.5S`) or human-readable duration (`1h 30m`).
+///
+/// Only ISO 8601 durations keep years and months as calendar units.
+pub(crate) fn parse_interval(raw: &str) -> anyhow::Result<PgInterval> {
+    match raw.strip_prefix('P') {
+        Some(iso) => {
+            parse_iso_8601(iso).with_context(|| format!("invalid ISO 8601 duration: {raw}"))
+        }
+        None => {
+            let duration = humantime::parse_duration(raw)?;
+            PgInterval::try_from(duration).map_err(|err| anyhow!(err))
+        }
+    }
+}
+
+fn parse_iso_8601(iso: &str) -> anyhow::Result<PgInterval> {
+    let mut interval = PgInterval {
+        months: 0,
+        days: 0,
+        microseconds: 0,
+    };
+    let (date, time) = iso.split_once('T').unwrap_or((iso, ""));
+    if date.is_empty() && time.is_empty() {
+        return Err(anyhow!("no duration components"));
+    }
+    let out_of_range = || anyhow!("interval out of range");
+    for (number, unit) in components(date)? {
+        let number: i32 = number.parse()?;
+        let (field, amount) = match unit {
+            'Y' => (&mut interval.months, number.checked_mul(12)),
+            'M' => (&mut interval.months, Some(number)),
+            'W' => (&mut interval.days, number.checked_mul(7)),
+            'D' => (&mut interval.days, Some(number)),
+            _ => return Err(anyhow!("unexpected date unit: {unit}")),
+        };
+        *field = amount
+            .and_then(|amount| field.checked_add(amount))
+            .ok_or_else(out_of_range)?;
+    }
+    for (number, unit) in components(time)? {
+        let seconds = match unit {
+            'H' => number.parse::<f64>()? * 3600f64,
+            'M' => number.parse::<f64>()? * 60f64,
+            'S' => number.parse::<f64>()?,
+            _ => return Err(anyhow!("unexpected time unit: {unit}")),
+        };
+        let microseconds = (seconds * MICROS_PER_SECOND).round();
+        // `as` saturates, values beyond the `i64` range must be rejected before the conversion
+        if !microseconds.is_finite() || microseconds.abs() >= i64::MAX as f64 {
+            return Err(out_of_range());
+        }
+        interval.microseconds = interval
+            .microseconds
+            .checked_add(microseconds as i64)
+            .ok_or_else(out_of_range)?;
+    }
+    Ok(interval)
+}
+
+/// Splits `1Y2M` into `[("1", 'Y'), ("2", 'M')]`.
+fn components(input: &str) -> anyhow::Result<Vec<(&str, char)>> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    for (idx, c) in input.char_indices() {
+        if c.is_ascii_alphabetic() {
+            if start == idx {
+                return Err(anyhow!("missing number before {c}"));
+            }
+            components.push((&input[start..idx], c));
+            start = idx + c.len_utf8();
+        }
+    }
+    if start != input.len() {
+        return Err(anyhow!("missing unit after {}", &input[start..]));
+    }
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_8601() {
+        //when
+        let interval = parse_interval("P1Y2M1W3DT4H5M6.5S").unwrap();
+
+        //then
+        assert_eq!(interval.months, 14);
+        assert_eq!(interval.days, 10);
+        assert_eq!(
+            interval.microseconds,
+            (4 * 3600 + 5 * 60 + 6) * 1_000_000 + 500_000
+        );
+    }
+
+    #[test]
+    fn test_iso_8601_time_only() {
+        //when
+        let interval = parse_interval("PT1.5M").unwrap();
+
+        //then
+        assert_eq!(interval.months, 0);
+        assert_eq!(interval.days, 0);
+        assert_eq!(interval.microseconds, 90_000_000);
+    }
+
+    #[test]
+    fn test_human_readable() {
+        //when
+        let interval = parse_interval("1h 30m 250ms").unwrap();
+
+        //then
+        assert_eq!(interval.microseconds, 5_400_250_000);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        for raw in [
+            "P300000000Y",
+            "P2147483647Y",
+            "P2000000000M2000000000M",
+            "P400000000W",
+            "P2000000000D2000000000D",
+            "PT3000000000000H",
+            "PT2562047788H2562047788H",
+        ] {
+            assert_eq!(
+                format!("{:#}", parse_interval(raw).unwrap_err()),
+                format!("invalid ISO 8601 duration: {raw}: interval out of range"),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse_interval("P").is_err());
+        assert!(parse_interval("P1").is_err());
+        assert!(parse_interval("PT1D").is_err());
+        assert!(parse_interval("P1.5D").is_err());
+        assert!(parse_interval("one hour").is_err());
+    }
+}
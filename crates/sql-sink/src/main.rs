@@ -1,14 +1,23 @@
 mod bind;
+mod call;
+mod cast;
 mod config;
 mod db;
+mod delete;
+mod ident;
 mod insert;
+mod interval;
 mod sink;
+mod snapshot;
+mod statement;
+mod truncate;
 mod upsert;
 
 use adaptive_backoff::prelude::{
     Backoff, BackoffBuilder, ExponentialBackoff, ExponentialBackoffBuilder,
 };
 use anyhow::{anyhow, Result};
+use cast::CastAllowlist;
 use config::SqlConfig;
 use futures::{SinkExt, StreamExt};
 
@@ -21,11 +30,16 @@ use fluvio_connector_common::{
 use fluvio_model_sql::Operation;
 
 use sink::SqlSink;
+use snapshot::SnapshotBuffer;
+use statement::StatementAllowlist;
 
 #[connector(sink)]
 async fn start(config: SqlConfig, mut stream: impl ConsumerStream) -> Result<()> {
     let mut backoff = backoff_init(&config)?;
     let mut sink = start_sink(&mut backoff, &config).await?;
+    let allowlist = StatementAllowlist::new(&config.allowed_statements);
+    let cast_allowlist = CastAllowlist::new(&config.allowed_cast_functions);
+    let mut snapshot = SnapshotBuffer::new(config.max_snapshot_operations);
 
     info!("Starting to process records");
 
@@ -40,6 +54,23 @@ async fn start(config: SqlConfig, mut stream: impl ConsumerStream) -> Result<()>
                     }
                 };
                 trace!(?operation, "Deserialized operation");
+                // the database enforces the allowlists too, checking them here skips a rejected
+                // operation instead of retrying it
+                if let Err(err) = allowlist
+                    .check(&operation)
+                    .and_then(|_| cast_allowlist.check(&operation))
+                {
+                    error!("Rejected operation: {}", err);
+                    continue;
+                }
+                let operation = match snapshot.push(operation) {
+                    Ok(Some(op)) => op,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        error!("Rejected operation: {}", err);
+                        continue;
+                    }
+                };
                 if let Err(err) = process_item(&mut sink, &mut backoff, &config, operation).await {
                     error!("Error processing item: {}", err);
                 }
@@ -6,25 +6,44 @@ use fluvio::Offset;
 use fluvio_connector_common::{tracing::info, LocalBoxSink, Result, Sink};
 use fluvio_model_sql::Operation;
 
-use crate::{config::SqlConfig, db::Db};
+use crate::{
+    bind::BindOptions, cast::CastAllowlist, config::SqlConfig, db::Db,
+    statement::StatementAllowlist,
+};
 
 #[derive(Debug)]
 pub(crate) struct SqlSink {
     url: Url,
+    bind_options: BindOptions,
+    statements: StatementAllowlist,
+    casts: CastAllowlist,
 }
 
 impl SqlSink {
     pub(crate) fn new(config: &SqlConfig) -> Result<Self> {
         let url = Url::parse(&config.url.resolve()?).context("unable to parse sql url")?;
 
-        Ok(Self { url })
+        let bind_options = BindOptions {
+            sqlite_big_integers: config.sqlite_big_integers,
+        };
+
+        Ok(Self {
+            url,
+            bind_options,
+            statements: StatementAllowlist::new(&config.allowed_statements),
+            casts: CastAllowlist::new(&config.allowed_cast_functions),
+        })
     }
 }
 
 #[async_trait]
 impl Sink<Operation> for SqlSink {
     async fn connect(self, _offset: Option<Offset>) -> Result<LocalBoxSink<Operation>> {
-        let db = Db::connect(self.url.as_str()).await?;
+        let db = Db::connect(self.url.as_str())
+            .await?
+            .with_bind_options(self.bind_options)
+            .with_allowed_statements(self.statements)
+            .with_allowed_cast_functions(self.casts);
         info!("connected to database {}", db.kind());
         let unfold = futures::sink::unfold(db, |mut db: Db, record: Operation| async move {
             db.execute(&record).await?;
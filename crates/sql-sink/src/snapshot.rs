@@ -0,0 +1,359 @@
+use anyhow::anyhow;
+
+use fluvio_connector_common::tracing::{debug, warn};
+use fluvio_model_sql::{Operation, Snapshot, Truncate};
+
+/// Collects the operations of the snapshotted table between `SnapshotBegin` and `SnapshotEnd`
+/// markers, operations of other tables pass through. Transactions writing to the table are
+/// collected as a whole, statements and calls are rejected as their tables are unknown.
+///
+/// The snapshot is sent to the database as a single transaction that truncates the table first,
+/// so readers never observe a partially loaded table, and a failed snapshot is retried as a whole.
+/// The buffer is held in memory only, a snapshot exceeding `max_operations` is discarded.
+pub(crate) struct SnapshotBuffer {
+    max_operations: usize,
+    current: Option<Current>,
+}
+
+struct Current {
+    table: String,
+    operations: Vec<Operation>,
+    /// Number of the collected operations, including the ones nested in transactions
+    size: usize,
+    overflowed: bool,
+}
+
+impl SnapshotBuffer {
+    pub(crate) fn new(max_operations: usize) -> Self {
+        Self {
+            max_operations,
+            current: None,
+        }
+    }
+
+    /// Returns the operation to execute now, or `None` if the operation was buffered.
+    pub(crate) fn push(&mut self, operation: Operation) -> anyhow::Result<Option<Operation>> {
+        match operation {
+            Operation::SnapshotBegin(Snapshot { table }) => {
+                if let Some(previous) = self.current.take() {
+                    warn!(
+                        table = previous.table,
+                        operations = previous.operations.len(),
+                        "Discarding unfinished snapshot"
+                    );
+                }
+                debug!(table, "Snapshot started");
+                let truncate = Operation::Truncate(Truncate {
+                    table: table.clone(),
+                });
+                self.current = Some(Current {
+                    table,
+                    operations: vec![truncate],
+                    size: 0,
+                    overflowed: false,
+                });
+                Ok(None)
+            }
+            Operation::SnapshotEnd(Snapshot { table }) => match self.current.take() {
+                Some(current) if current.table == table && current.overflowed => Err(anyhow!(
+                    "snapshot of table {table} exceeded {} operations and was discarded",
+                    self.max_operations
+                )),
+                Some(current) if current.table == table => {
+                    debug!(
+                        table,
+                        operations = current.operations.len(),
+                        "Snapshot finished"
+                    );
+                    Ok(Some(Operation::Transaction(current.operations)))
+                }
+                Some(current) => {
+                    let in_progress = current.table.clone();
+                    self.current = Some(current);
+                    Err(anyhow!(
+                        "snapshot end for table {table} while snapshot of {in_progress} is in progress"
+                    ))
+                }
+                None => Err(anyhow!(
+                    "snapshot end for table {table} without snapshot begin"
+                )),
+            },
+            operation => {
+                let Some(current) = self.current.as_mut() else {
+                    return Ok(Some(operation));
+                };
+                match writes_to(&operation, &current.table) {
+                    Some(false) => return Ok(Some(operation)),
+                    Some(true) => {}
+                    None => {
+                        return Err(anyhow!(
+                            "statement or call can't be executed during snapshot of {}",
+                            current.table
+                        ))
+                    }
+                }
+                if current.overflowed {
+                    return Ok(None);
+                }
+                current.size += size_of(&operation);
+                if current.size > self.max_operations {
+                    warn!(
+                        table = current.table,
+                        max_operations = self.max_operations,
+                        "Snapshot is too large, discarding it"
+                    );
+                    current.operations = Vec::new();
+                    current.overflowed = true;
+                    return Ok(None);
+                }
+                current.operations.push(operation);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Whether the operation writes to the table, `None` if it may write to any table.
+fn writes_to(operation: &Operation, table: &str) -> Option<bool> {
+    match operation {
+        Operation::Insert(data) => Some(data.table == table),
+        Operation::Upsert(data) => Some(data.table == table),
+        Operation::Delete(data) => Some(data.table == table),
+        Operation::Truncate(data) => Some(data.table == table),
+        Operation::Transaction(operations) => {
+            let mut writes = Some(false);
+            for operation in operations {
+                match writes_to(operation, table) {
+                    Some(true) => return Some(true),
+                    Some(false) => {}
+                    None => writes = None,
+                }
+            }
+            writes
+        }
+        Operation::Statement(_) | Operation::Call(_) => None,
+        Operation::SnapshotBegin(_) | Operation::SnapshotEnd(_) => Some(false),
+    }
+}
+
+fn size_of(operation: &Operation) -> usize {
+    match operation {
+        Operation::Transaction(operations) => operations.iter().map(size_of).sum(),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::{Insert, Statement, Type, Value};
+
+    fn make_insert(table: &str) -> Operation {
+        Operation::Insert(Insert {
+            table: table.to_string(),
+            values: vec![Value {
+                column: "id".to_string(),
+                raw_value: "1".to_string(),
+                type_: Type::Int,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            }],
+        })
+    }
+
+    fn begin(table: &str) -> Operation {
+        Operation::SnapshotBegin(Snapshot {
+            table: table.to_string(),
+        })
+    }
+
+    fn end(table: &str) -> Operation {
+        Operation::SnapshotEnd(Snapshot {
+            table: table.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_pass_through_without_snapshot() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+
+        //when
+        let res = buffer.push(make_insert("t")).unwrap();
+
+        //then
+        assert_eq!(res, Some(make_insert("t")));
+    }
+
+    #[test]
+    fn test_snapshot_to_transaction() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+
+        //when
+        assert_eq!(buffer.push(begin("t")).unwrap(), None);
+        assert_eq!(buffer.push(make_insert("t")).unwrap(), None);
+        assert_eq!(buffer.push(make_insert("t")).unwrap(), None);
+        let res = buffer.push(end("t")).unwrap();
+
+        //then
+        assert_eq!(
+            res,
+            Some(Operation::Transaction(vec![
+                Operation::Truncate(Truncate {
+                    table: "t".to_string()
+                }),
+                make_insert("t"),
+                make_insert("t"),
+            ]))
+        );
+        assert_eq!(
+            buffer.push(make_insert("t")).unwrap(),
+            Some(make_insert("t"))
+        );
+    }
+
+    #[test]
+    fn test_other_tables_pass_through() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+
+        //when
+        buffer.push(begin("t")).unwrap();
+        let other = buffer.push(make_insert("other")).unwrap();
+        buffer.push(make_insert("t")).unwrap();
+        let res = buffer.push(end("t")).unwrap();
+
+        //then
+        assert_eq!(other, Some(make_insert("other")));
+        assert_eq!(
+            res,
+            Some(Operation::Transaction(vec![
+                Operation::Truncate(Truncate {
+                    table: "t".to_string()
+                }),
+                make_insert("t"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_too_large_snapshot_is_discarded() {
+        //given
+        let mut buffer = SnapshotBuffer::new(2);
+
+        //when
+        buffer.push(begin("t")).unwrap();
+        for _ in 0..3 {
+            assert_eq!(buffer.push(make_insert("t")).unwrap(), None);
+        }
+        let res = buffer.push(end("t"));
+
+        //then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "snapshot of table t exceeded 2 operations and was discarded"
+        );
+        assert_eq!(
+            buffer.push(make_insert("t")).unwrap(),
+            Some(make_insert("t"))
+        );
+    }
+
+    #[test]
+    fn test_restarted_snapshot_discards_previous() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+
+        //when
+        buffer.push(begin("t")).unwrap();
+        buffer.push(make_insert("t")).unwrap();
+        buffer.push(begin("t")).unwrap();
+        let res = buffer.push(end("t")).unwrap();
+
+        //then
+        assert_eq!(
+            res,
+            Some(Operation::Transaction(vec![Operation::Truncate(
+                Truncate {
+                    table: "t".to_string()
+                }
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_unmatched_snapshot_end() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+
+        //when
+        let without_begin = buffer.push(end("t"));
+        buffer.push(begin("t")).unwrap();
+        let other_table = buffer.push(end("other"));
+
+        //then
+        assert_eq!(
+            without_begin.unwrap_err().to_string(),
+            "snapshot end for table t without snapshot begin"
+        );
+        assert_eq!(
+            other_table.unwrap_err().to_string(),
+            "snapshot end for table other while snapshot of t is in progress"
+        );
+        assert!(buffer.push(end("t")).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_transaction_of_snapshotted_table_is_collected() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+        let transaction = Operation::Transaction(vec![make_insert("other"), make_insert("t")]);
+        let other_transaction = Operation::Transaction(vec![make_insert("other")]);
+
+        //when
+        buffer.push(begin("t")).unwrap();
+        let collected = buffer.push(transaction.clone()).unwrap();
+        let passed = buffer.push(other_transaction.clone()).unwrap();
+        let res = buffer.push(end("t")).unwrap();
+
+        //then
+        assert_eq!(collected, None);
+        assert_eq!(passed, Some(other_transaction));
+        assert_eq!(
+            res,
+            Some(Operation::Transaction(vec![
+                Operation::Truncate(Truncate {
+                    table: "t".to_string()
+                }),
+                transaction,
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_statement_during_snapshot_is_rejected() {
+        //given
+        let mut buffer = SnapshotBuffer::new(100);
+        let statement = Operation::Statement(Statement {
+            sql: "DELETE FROM t".to_string(),
+            params: vec![],
+        });
+
+        //when
+        let before = buffer.push(statement.clone()).unwrap();
+        buffer.push(begin("t")).unwrap();
+        let during = buffer.push(statement.clone());
+        let in_transaction = buffer.push(Operation::Transaction(vec![statement]));
+
+        //then
+        assert!(before.is_some());
+        assert_eq!(
+            during.unwrap_err().to_string(),
+            "statement or call can't be executed during snapshot of t"
+        );
+        assert!(in_transaction.is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+
+use fluvio_model_sql::Operation;
+
+/// Operator-approved SQL statement templates.
+///
+/// Templates are compared with whitespace outside of quotes collapsed, so multi-line definitions
+/// in the config match single-line statements and vice versa.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatementAllowlist {
+    statements: HashSet<String>,
+}
+
+impl StatementAllowlist {
+    pub(crate) fn new(statements: &[String]) -> Self {
+        Self {
+            statements: statements.iter().map(|s| normalize(s)).collect(),
+        }
+    }
+
+    /// Checks that every `Statement` in the operation, including nested ones, is allowed.
+    pub(crate) fn check(&self, operation: &Operation) -> anyhow::Result<()> {
+        match operation {
+            Operation::Statement(statement) => {
+                if self.statements.contains(&normalize(&statement.sql)) {
+                    Ok(())
+                } else {
+                    Err(anyhow!(
+                        "statement is not in allowed-statements: {}",
+                        statement.sql
+                    ))
+                }
+            }
+            Operation::Transaction(operations) => {
+                operations.iter().try_for_each(|op| self.check(op))
+            }
+            Operation::Insert(_)
+            | Operation::Upsert(_)
+            | Operation::Call(_)
+            | Operation::Truncate(_)
+            | Operation::Delete(_)
+            | Operation::SnapshotBegin(_)
+            | Operation::SnapshotEnd(_) => Ok(()),
+        }
+    }
+}
+
+/// Collapses whitespace runs outside of string literals and quoted identifiers into one space.
+fn normalize(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut pending_space = false;
+    for c in sql.trim().chars() {
+        match quote {
+            Some(q) => {
+                normalized.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => pending_space = true,
+            None => {
+                if pending_space {
+                    normalized.push(' ');
+                    pending_space = false;
+                }
+                if c == '\'' || c == '"' {
+                    quote = Some(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::{Statement, Type, Value};
+
+    fn make_statement(sql: &str) -> Operation {
+        Operation::Statement(Statement {
+            sql: sql.to_string(),
+            params: vec![Value {
+                column: "id".to_string(),
+                raw_value: "1".to_string(),
+                type_: Type::Int,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            }],
+        })
+    }
+
+    #[test]
+    fn test_allowed_statement() {
+        //given
+        let allowlist = StatementAllowlist::new(&[
+            "UPDATE orders SET status = 'done'\n  WHERE id = $1".to_string(),
+        ]);
+
+        //when
+        let res = allowlist.check(&make_statement(
+            "UPDATE orders  SET status = 'done' WHERE id = $1",
+        ));
+
+        //then
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_rejected_statement() {
+        //given
+        let allowlist = StatementAllowlist::new(&["CALL refresh($1)".to_string()]);
+
+        //when
+        let res = allowlist.check(&make_statement("DROP TABLE orders"));
+
+        //then
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "statement is not in allowed-statements: DROP TABLE orders"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_in_literals_is_kept() {
+        //given
+        let allowlist = StatementAllowlist::new(&[
+            "UPDATE orders SET status = 'in  progress' WHERE id = $1".to_string(),
+        ]);
+
+        //when
+        let res = allowlist.check(&make_statement(
+            "UPDATE orders SET status = 'in progress' WHERE id = $1",
+        ));
+
+        //then
+        assert!(res.is_err());
+        assert_eq!(
+            normalize("SELECT  'a  b',\n \"c  d\"   FROM t"),
+            "SELECT 'a  b', \"c  d\" FROM t"
+        );
+        assert_eq!(normalize("SELECT 'it''s  ok'  "), "SELECT 'it''s  ok'");
+    }
+
+    #[test]
+    fn test_empty_allowlist_rejects_nested_statement() {
+        //given
+        let allowlist = StatementAllowlist::new(&[]);
+
+        //when
+        let res = allowlist.check(&Operation::Transaction(vec![make_statement(
+            "CALL refresh($1)",
+        )]));
+
+        //then
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use sqlx::{Database, Postgres, Sqlite};
+
+use crate::db::Db;
+
+pub trait Truncate<DB: Database> {
+    fn truncate_query(table: &str) -> String;
+}
+
+impl Truncate<Postgres> for Db {
+    fn truncate_query(table: &str) -> String {
+        format!("TRUNCATE TABLE {table}")
+    }
+}
+
+impl Truncate<Sqlite> for Db {
+    fn truncate_query(table: &str) -> String {
+        format!("DELETE FROM {table}")
+    }
+}
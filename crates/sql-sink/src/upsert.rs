@@ -2,6 +2,7 @@ use fluvio_model_sql::Value;
 use itertools::Itertools;
 use sqlx::{Database, Postgres, Sqlite};
 
+use crate::bind::{pg_placeholders, sqlite_placeholders};
 use crate::db::Db;
 
 pub trait Upsert<DB: Database> {
@@ -11,15 +12,8 @@ pub trait Upsert<DB: Database> {
 impl Upsert<Postgres> for Db {
     fn upsert_query(table: &str, values: &[Value], uniq_idx: &str) -> String {
         let columns = values.iter().map(|v| v.column.as_str()).join(",");
-        let values_clause = (1..=values.len()).map(|i| format!("${i}")).join(",");
-        let set_clause = values
-            .iter()
-            .enumerate()
-            .map(|(i, v)| {
-                let idx = i + values.len() + 1;
-                format!("{}=${idx}", v.column)
-            })
-            .join(",");
+        let values_clause = pg_placeholders(values);
+        let set_clause = excluded_set_clause(values);
         format!("INSERT INTO {table} ({columns}) VALUES ({values_clause}) ON CONFLICT({uniq_idx}) DO UPDATE SET {set_clause}")
     }
 }
@@ -27,8 +21,84 @@ impl Upsert<Postgres> for Db {
 impl Upsert<Sqlite> for Db {
     fn upsert_query(table: &str, values: &[Value], uniq_idx: &str) -> String {
         let columns = values.iter().map(|v| v.column.as_str()).join(",");
-        let values_clause = (1..=values.len()).map(|_| "?").join(",");
-        let set_clause = values.iter().map(|v| format!("{}=?", v.column)).join(",");
+        let values_clause = sqlite_placeholders(values);
+        let set_clause = excluded_set_clause(values);
         format!("INSERT INTO {table} ({columns}) VALUES ({values_clause}) ON CONFLICT({uniq_idx}) DO UPDATE SET {set_clause}")
     }
 }
+
+/// Builds the `DO UPDATE SET` clause referencing the row proposed for insertion,
+/// so every value is bound only once.
+fn excluded_set_clause(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{col}=EXCLUDED.{col}", col = v.column))
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluvio_model_sql::Type;
+
+    fn make_values(count: usize) -> Vec<Value> {
+        (0..count)
+            .map(|i| Value {
+                column: format!("col_{i}"),
+                raw_value: i.to_string(),
+                type_: Type::Int,
+                cast: None,
+                encoding: None,
+                generated: None,
+                typed_value: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_upsert_query_postgres() {
+        //given
+        let values = make_values(2);
+
+        //when
+        let sql = <Db as Upsert<Postgres>>::upsert_query("test_table", &values, "col_0");
+
+        //then
+        assert_eq!(
+            sql,
+            "INSERT INTO test_table (col_0,col_1) VALUES ($1,$2) ON CONFLICT(col_0) DO UPDATE SET col_0=EXCLUDED.col_0,col_1=EXCLUDED.col_1"
+        );
+    }
+
+    #[test]
+    fn test_upsert_query_sqlite() {
+        //given
+        let values = make_values(2);
+
+        //when
+        let sql = <Db as Upsert<Sqlite>>::upsert_query("test_table", &values, "col_0");
+
+        //then
+        assert_eq!(
+            sql,
+            "INSERT INTO test_table (col_0,col_1) VALUES (?,?) ON CONFLICT(col_0) DO UPDATE SET col_0=EXCLUDED.col_0,col_1=EXCLUDED.col_1"
+        );
+    }
+
+    #[test]
+    fn test_upsert_query_wide_table_binds_each_value_once() {
+        //given
+        let values = make_values(1500);
+
+        //when
+        let pg_sql = <Db as Upsert<Postgres>>::upsert_query("wide_table", &values, "col_0");
+        let sqlite_sql = <Db as Upsert<Sqlite>>::upsert_query("wide_table", &values, "col_0");
+
+        //then
+        assert!(pg_sql.contains("$1500)"));
+        assert!(!pg_sql.contains("$1501"));
+        assert_eq!(sqlite_sql.matches('?').count(), 1500);
+        assert!(pg_sql.ends_with("col_1499=EXCLUDED.col_1499"));
+        assert!(sqlite_sql.ends_with("col_1499=EXCLUDED.col_1499"));
+    }
+}
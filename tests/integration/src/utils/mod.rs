@@ -56,61 +56,109 @@ pub(crate) fn generate_records(table: &str, count: usize) -> Result<Vec<Insert>>
                     column: "json_col".to_string(),
                     raw_value: "{\"json_key\":\"json_value\"}".to_string(),
                     type_: Type::Json,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "bool_col".to_string(),
                     raw_value: "true".to_string(),
                     type_: Type::Bool,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "smallint_col".to_string(),
                     raw_value: i.to_string(),
                     type_: Type::SmallInt,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "int_col".to_string(),
                     raw_value: i.to_string(),
                     type_: Type::Int,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "bigint_col".to_string(),
                     raw_value: i.to_string(),
                     type_: Type::BigInt,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "text_col".to_string(),
                     raw_value: "some text".to_string(),
                     type_: Type::Text,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "bytes_col".to_string(),
                     raw_value: "some bytes".to_string(),
                     type_: Type::Bytes,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "float_col".to_string(),
                     raw_value: "3.123".to_string(),
                     type_: Type::Float,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "double_col".to_string(),
                     raw_value: "3.333333333".to_string(),
                     type_: Type::DoublePrecision,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "numeric_col".to_string(),
                     raw_value: rust_decimal::Decimal::TEN.to_string(),
                     type_: Type::Numeric,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "timestamp_col".to_string(),
                     raw_value: chrono::Utc::now().naive_local().to_string(),
                     type_: Type::Timestamp,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "date_col".to_string(),
                     raw_value: chrono::Utc::now().naive_local().date().to_string(),
                     type_: Type::Date,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "time_col".to_string(),
@@ -120,16 +168,28 @@ pub(crate) fn generate_records(table: &str, count: usize) -> Result<Vec<Insert>>
                         .format("%H:%M:%S")
                         .to_string(),
                     type_: Type::Time,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "uuid_col".to_string(),
                     raw_value: uuid::Uuid::new_v4().to_string(),
                     type_: Type::Uuid,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "char_col".to_string(),
                     raw_value: "126".to_string(),
-                    type_: Type::Char,
+                    type_: Type::TinyInt,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
             ],
         };
@@ -148,11 +208,19 @@ pub(crate) fn generate_raw_records(table: &str, start: usize, end: usize) -> Res
                     column: "device_id".to_string(),
                     raw_value: i.to_string(),
                     type_: Type::Int,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
                 Value {
                     column: "record".to_string(),
                     raw_value: format!("{{\"device\":{{\"device_id\":{i}}}}}"),
                     type_: Type::Json,
+                    cast: None,
+                    encoding: None,
+                    generated: None,
+                    typed_value: None,
                 },
             ],
         };